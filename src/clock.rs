@@ -0,0 +1,42 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Where a [`Game`](crate::engine::Game) gets the current time from
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The time of the system, used when playing
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to, for simulating games without waiting
+pub struct ManualClock {
+    now: Mutex<Instant>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self {
+            now: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl ManualClock {
+    /// Moves the clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().expect("the clock is never poisoned") += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().expect("the clock is never poisoned")
+    }
+}
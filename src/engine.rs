@@ -0,0 +1,736 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::clock::{Clock, SystemClock};
+use crate::lifelines::{Lifeline, Lifelines};
+use crate::replay::{Replay, RoundReplay, Timed};
+use crate::reveal::Reveal;
+use crate::settings::{Breakdown, Settings};
+use crate::Code;
+
+pub const MAX_POINTS: i32 = 12;
+
+/// The share of the round's time left with `points` steps to go and `since_step` passed since the
+/// last step, the round ends a step after the points reached 0
+fn time_left(points: i32, since_step: Duration, step: Duration) -> f64 {
+    let left = step * points as u32 + step.saturating_sub(since_step);
+    left.as_secs_f64() / (step * (MAX_POINTS + 1) as u32).as_secs_f64()
+}
+
+fn shown_chars(points: i32) -> i32 {
+    2i32.pow((MAX_POINTS - points).max(0) as u32)
+}
+
+/// How a round ended
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    /// The player chose one of the options
+    Guess,
+    /// The points reached zero before the player chose an option
+    Timeout,
+    /// The player gave up during the round
+    Quit,
+    /// The player used the skip lifeline, no life was lost
+    Skip,
+    /// Another player answered correctly first, no life was lost
+    Beaten,
+}
+
+/// Everything that happened in a single round
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Round {
+    pub code: Code,
+    /// The index into `code.options` of the option the player chose
+    pub answer: Option<usize>,
+    /// The time it took the player to answer, without the time the game was paused
+    pub elapsed_ms: u64,
+    /// How many graphemes of the code were visible when the round ended
+    pub visible_chars: usize,
+    pub points: i32,
+    pub outcome: Outcome,
+    /// The lifelines used in this round
+    #[serde(default)]
+    pub lifelines: Vec<Lifeline>,
+    /// How `points` came together, all zero if the answer was not correct
+    #[serde(default)]
+    pub breakdown: Breakdown,
+}
+
+impl Round {
+    pub fn is_correct(&self) -> bool {
+        self.answer == Some(self.code.language)
+    }
+
+    /// Whether the round cost a life, because the answer was wrong or the time ran out
+    pub fn is_missed(&self) -> bool {
+        match self.outcome {
+            Outcome::Guess => !self.is_correct(),
+            Outcome::Timeout => true,
+            Outcome::Quit | Outcome::Skip | Outcome::Beaten => false,
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.code.options[self.code.language]
+    }
+
+    pub fn answered_language(&self) -> Option<&str> {
+        self.answer.map(|answer| self.code.options[answer].as_str())
+    }
+}
+
+/// What can happen to a [`Game`]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Event {
+    /// Time passed, the points of the round decrease every step and the round times out a step
+    /// after they reached zero
+    Tick,
+    /// The player chose the option with this index
+    Answer(usize),
+    /// Stops the clock of the round, ignored if it is paused already
+    Pause,
+    /// Starts the clock of a paused round again
+    Resume,
+    Lifeline(Lifeline),
+    /// The player gave up, during a round it ends with [`Outcome::Quit`]
+    Quit,
+    /// Another player answered correctly first, the round ends with [`Outcome::Beaten`]
+    Beaten,
+}
+
+/// The round that is being played
+pub struct Current {
+    code: Code,
+    reveal: Reveal,
+    /// The points left, decreasing with every step
+    points: i32,
+    /// The grapheme the revealed code is centered around
+    origin: usize,
+    /// Options taken away by the 50/50 lifeline
+    removed: BTreeSet<usize>,
+    lifelines: Vec<Lifeline>,
+    started: Instant,
+    /// When the points decreased the last time, moved forward by pauses
+    last_step: Instant,
+    paused_total: Duration,
+    /// The clock of the round is stopped while this is set
+    paused: Option<Instant>,
+}
+
+impl Current {
+    pub fn code(&self) -> &Code {
+        &self.code
+    }
+
+    pub fn points(&self) -> i32 {
+        self.points
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// The event a pause key sends, pausing the round or resuming it if it is paused
+    pub fn toggle_pause(&self) -> Event {
+        if self.is_paused() {
+            Event::Resume
+        } else {
+            Event::Pause
+        }
+    }
+
+    /// The grapheme the revealed code is centered around
+    pub fn origin(&self) -> usize {
+        self.origin
+    }
+
+    pub fn removed(&self) -> &BTreeSet<usize> {
+        &self.removed
+    }
+
+    /// The part of the code revealed so far, all of it once the points reached zero
+    pub fn shown_text(&self) -> &str {
+        self.reveal.window(self.origin, self.visible_chars())
+    }
+
+    fn visible_chars(&self) -> usize {
+        if self.points == 0 {
+            self.reveal.len()
+        } else {
+            (shown_chars(self.points) as usize).min(self.reveal.len())
+        }
+    }
+
+    /// The wrong options that were not taken away yet
+    fn wrong_options(&self) -> Vec<usize> {
+        (0..self.code.options.len())
+            .filter(|&idx| idx != self.code.language && !self.removed.contains(&idx))
+            .collect()
+    }
+
+    fn since_step(&self, now: Instant) -> Duration {
+        self.paused.unwrap_or(now).duration_since(self.last_step)
+    }
+
+    /// The time the round was played until `now`, without pauses
+    fn elapsed(&self, now: Instant) -> Duration {
+        now.duration_since(self.started)
+            .saturating_sub(self.paused_total)
+            .saturating_sub(
+                self.paused
+                    .map_or(Duration::ZERO, |paused_at| now.duration_since(paused_at)),
+            )
+    }
+}
+
+/// A game from the first round until the player runs out of lives or gives up.
+///
+/// The game does not fetch code or read input itself, a frontend starts every round with
+/// [`Game::start_round`] and passes on what happens with [`Game::handle`]. With a
+/// [`ManualClock`](crate::clock::ManualClock) and a [`seed`](Game::seed) a whole game can be
+/// simulated deterministically.
+pub struct Game {
+    settings: Settings,
+    clock: Arc<dyn Clock>,
+    seed: u64,
+    /// Chooses the revealed part of the code and the options removed by the 50/50 lifeline
+    rng: StdRng,
+    points: i32,
+    lives: usize,
+    /// Correct answers in a row
+    streak: usize,
+    lifelines: Lifelines,
+    rounds: Vec<Round>,
+    current: Option<Current>,
+    quit: bool,
+    /// What happened in every round so far, for the replay
+    log: Vec<RoundReplay>,
+}
+
+impl Game {
+    pub fn new(settings: Settings) -> Self {
+        let seed = rand::random();
+        Self {
+            points: 0,
+            lives: settings.mode.lives(),
+            streak: 0,
+            lifelines: Lifelines::default(),
+            rounds: Vec::new(),
+            current: None,
+            quit: false,
+            settings,
+            clock: Arc::new(SystemClock),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            log: Vec::new(),
+        }
+    }
+
+    /// Replaces where the time is taken from
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Makes the random choices of the game reproducible
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Goes on with the `streak` of correct answers of an earlier game
+    pub fn initial_streak(mut self, streak: usize) -> Self {
+        self.streak = streak;
+        self
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn points(&self) -> i32 {
+        self.points
+    }
+
+    pub fn lives(&self) -> usize {
+        self.lives
+    }
+
+    pub fn streak(&self) -> usize {
+        self.streak
+    }
+
+    /// The multiplier a correct answer in the next round gets
+    pub fn next_multiplier(&self) -> i32 {
+        self.settings
+            .scoring
+            .score(MAX_POINTS, MAX_POINTS, 1., self.streak + 1)
+            .multiplier
+    }
+
+    pub fn lifelines(&self) -> &Lifelines {
+        &self.lifelines
+    }
+
+    /// The rounds played so far, the last one is the one that ended most recently
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    pub fn into_rounds(self) -> Vec<Round> {
+        self.rounds
+    }
+
+    pub fn current(&self) -> Option<&Current> {
+        self.current.as_ref()
+    }
+
+    /// Everything needed to play the game so far again, `started_at` and `finished_at` are only
+    /// kept to tell games apart and for the results
+    pub fn replay(&self, started_at: DateTime<Local>, finished_at: DateTime<Local>) -> Replay {
+        Replay {
+            started_at,
+            finished_at,
+            settings: self.settings.clone(),
+            seed: self.seed,
+            rounds: self.log.clone(),
+        }
+    }
+
+    /// Whether no more rounds can be played
+    pub fn is_over(&self) -> bool {
+        self.quit
+            || self.lives == 0
+            || self
+                .settings
+                .mode
+                .rounds()
+                .filter(|&rounds| self.rounds.len() >= rounds)
+                .is_some()
+    }
+
+    /// The points a correct answer would get right now, without bonus and multiplier
+    pub fn round_points(&self) -> i32 {
+        match &self.current {
+            Some(current) => {
+                let step = self.settings.difficulty.step_duration();
+                self.settings.scoring.base(
+                    current.points,
+                    MAX_POINTS,
+                    time_left(current.points, current.since_step(self.clock.now()), step),
+                )
+            }
+            None => 0,
+        }
+    }
+
+    /// Starts a round with `code`, the clock starts right away
+    pub fn start_round(&mut self, mut code: Code) {
+        let reveal = Reveal::new(&code.code);
+        let origin = match code.origin.filter(|&origin| origin < reveal.len()) {
+            Some(origin) => origin,
+            None => reveal.origins().choose(&mut self.rng).copied().unwrap_or(0),
+        };
+        let now = self.clock.now();
+        self.log.push(RoundReplay {
+            code: code.clone(),
+            origin,
+            events: Vec::new(),
+        });
+        // kept with the round, so it can be played the same way again
+        code.origin = Some(origin);
+        self.current = Some(Current {
+            code,
+            reveal,
+            points: MAX_POINTS,
+            origin,
+            removed: BTreeSet::new(),
+            lifelines: Vec::new(),
+            started: now,
+            last_step: now,
+            paused_total: Duration::ZERO,
+            paused: None,
+        });
+    }
+
+    /// Applies `event` to the game, events that are not possible right now are ignored.
+    ///
+    /// Returns whether the round ended, it is the last of [`Game::rounds`] then.
+    pub fn handle(&mut self, event: Event) -> bool {
+        let now = self.clock.now();
+        let current = match &mut self.current {
+            Some(current) => current,
+            None => {
+                if event == Event::Quit {
+                    self.quit = true;
+                }
+                return false;
+            }
+        };
+        let log = &mut self.log.last_mut().expect("the round was logged").events;
+        let at = now.duration_since(current.started);
+        // every tick changing nothing would only bloat the replay
+        if event != Event::Tick {
+            log.push(Timed { at, event });
+        }
+        match event {
+            Event::Tick => {
+                if current.paused.is_none()
+                    && now.duration_since(current.last_step)
+                        > self.settings.difficulty.step_duration()
+                {
+                    log.push(Timed { at, event });
+                    if current.points == 0 {
+                        self.finish(None, Outcome::Timeout);
+                        return true;
+                    }
+                    current.points -= 1;
+                    current.last_step = now;
+                }
+            }
+            Event::Answer(answer) => {
+                if current.paused.is_none()
+                    && answer < current.code.options.len()
+                    && !current.removed.contains(&answer)
+                {
+                    self.finish(Some(answer), Outcome::Guess);
+                    return true;
+                }
+            }
+            Event::Pause => {
+                current.paused.get_or_insert(now);
+            }
+            Event::Resume => {
+                if let Some(paused_at) = current.paused.take() {
+                    current.last_step += now.duration_since(paused_at);
+                    current.paused_total += now.duration_since(paused_at);
+                }
+            }
+            Event::Lifeline(lifeline) if current.paused.is_none() => match lifeline {
+                Lifeline::Skip if self.lifelines.take(Lifeline::Skip) => {
+                    current.lifelines.push(Lifeline::Skip);
+                    self.finish(None, Outcome::Skip);
+                    return true;
+                }
+                Lifeline::Reveal if current.points > 1 && self.lifelines.take(Lifeline::Reveal) => {
+                    current.lifelines.push(Lifeline::Reveal);
+                    current.points -= 1;
+                    current.last_step = now;
+                }
+                // with a single wrong option left there is nothing to take away half of
+                Lifeline::FiftyFifty
+                    if !current.lifelines.contains(&Lifeline::FiftyFifty)
+                        && current.wrong_options().len() >= 2
+                        && self.lifelines.take(Lifeline::FiftyFifty) =>
+                {
+                    current.lifelines.push(Lifeline::FiftyFifty);
+                    let wrong = current.wrong_options();
+                    current.removed.extend(
+                        wrong
+                            .choose_multiple(&mut self.rng, wrong.len() / 2)
+                            .copied(),
+                    );
+                }
+                _ => {}
+            },
+            Event::Lifeline(_) => {}
+            Event::Quit => {
+                self.quit = true;
+                self.finish(None, Outcome::Quit);
+                return true;
+            }
+            Event::Beaten => {
+                self.finish(None, Outcome::Beaten);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Ends the current round, scoring it and updating lives and streak
+    fn finish(&mut self, answer: Option<usize>, outcome: Outcome) {
+        let now = self.clock.now();
+        let current = self.current.take().expect("a round is being played");
+        let breakdown = if answer == Some(current.code.language) {
+            self.streak += 1;
+            let step = self.settings.difficulty.step_duration();
+            let mut breakdown = self.settings.scoring.score(
+                current.points,
+                MAX_POINTS,
+                time_left(current.points, current.since_step(now), step),
+                self.streak,
+            );
+            // only a life that was lost before can be regained
+            breakdown.regained_life = self.settings.scoring.regenerates(self.streak)
+                && self.lives < self.settings.mode.lives();
+            if breakdown.regained_life {
+                self.lives += 1;
+            }
+            breakdown
+        } else {
+            if outcome != Outcome::Skip {
+                self.streak = 0;
+            }
+            Breakdown::default()
+        };
+        let round = Round {
+            answer,
+            elapsed_ms: current.elapsed(now).as_millis() as u64,
+            visible_chars: current.visible_chars(),
+            points: breakdown.points(),
+            outcome,
+            code: current.code,
+            lifelines: current.lifelines,
+            breakdown,
+        };
+        if round.is_missed() {
+            self.lives -= 1;
+        } else {
+            self.points += round.points;
+        }
+        self.rounds.push(round);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::providers::pack::{Pack, PackProvider};
+    use crate::settings::Mode;
+    use crate::CodeProvider;
+
+    fn code() -> Code {
+        Code {
+            reference: "test".into(),
+            code: "fn main() {\n    println!(\"Hello, world!\");\n}\n".into(),
+            language: 0,
+            options: vec!["Rust".into(), "C".into(), "Go".into(), "Java".into()],
+            origin: Some(0),
+        }
+    }
+
+    fn game(mode: Mode) -> (Game, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::default());
+        let settings = Settings {
+            mode,
+            ..Settings::default()
+        };
+        let game = Game::new(settings).clock(clock.clone()).seed(1);
+        (game, clock)
+    }
+
+    /// Moves the clock just past the next step and lets the game notice it
+    fn step(game: &mut Game, clock: &ManualClock) -> bool {
+        clock.advance(game.settings().difficulty.step_duration() + Duration::from_millis(1));
+        game.handle(Event::Tick)
+    }
+
+    #[test]
+    fn correct_answer_scores() {
+        let (mut game, _) = game(Mode::Classic);
+        game.start_round(code());
+        assert!(game.handle(Event::Answer(0)));
+        let round = &game.rounds()[0];
+        assert!(round.is_correct());
+        assert_eq!(round.outcome, Outcome::Guess);
+        // all points left and the quick bonus
+        assert_eq!(round.points, MAX_POINTS + 3);
+        assert_eq!(game.points(), MAX_POINTS + 3);
+        assert_eq!(game.lives(), 5);
+        assert_eq!(game.streak(), 1);
+        assert!(game.current().is_none());
+    }
+
+    #[test]
+    fn wrong_answer_costs_a_life() {
+        let (mut game, _) = game(Mode::Classic);
+        game.start_round(code());
+        assert!(game.handle(Event::Answer(2)));
+        let round = &game.rounds()[0];
+        assert!(!round.is_correct());
+        assert!(round.is_missed());
+        assert_eq!(round.answered_language(), Some("Go"));
+        assert_eq!(round.points, 0);
+        assert_eq!(game.points(), 0);
+        assert_eq!(game.lives(), 4);
+        assert_eq!(game.streak(), 0);
+    }
+
+    #[test]
+    fn round_times_out_a_step_after_the_points_ran_out() {
+        let (mut game, clock) = game(Mode::Classic);
+        game.start_round(code());
+        for points in (0..MAX_POINTS).rev() {
+            assert!(!step(&mut game, &clock));
+            assert_eq!(game.current().map(Current::points), Some(points));
+        }
+        assert!(step(&mut game, &clock));
+        let round = &game.rounds()[0];
+        assert_eq!(round.outcome, Outcome::Timeout);
+        assert!(round.is_missed());
+        assert_eq!(round.visible_chars, Reveal::new(&code().code).len());
+        assert_eq!(game.lives(), 4);
+    }
+
+    #[test]
+    fn skip_keeps_lives_and_streak() {
+        let (mut game, _) = game(Mode::Classic);
+        game.start_round(code());
+        game.handle(Event::Answer(0));
+        game.start_round(code());
+        assert!(game.handle(Event::Lifeline(Lifeline::Skip)));
+        let round = &game.rounds()[1];
+        assert_eq!(round.outcome, Outcome::Skip);
+        assert!(!round.is_missed());
+        assert_eq!(round.points, 0);
+        assert_eq!(game.lives(), 5);
+        assert_eq!(game.streak(), 1);
+
+        // it can only be used once
+        game.start_round(code());
+        assert!(!game.handle(Event::Lifeline(Lifeline::Skip)));
+        assert!(game.current().is_some());
+    }
+
+    #[test]
+    fn paused_time_is_not_counted() {
+        let (mut game, clock) = game(Mode::Classic);
+        game.start_round(code());
+        clock.advance(Duration::from_secs(1));
+        game.handle(Event::Pause);
+        assert!(game.current().unwrap().is_paused());
+        // a second pause does not resume the round
+        game.handle(Event::Pause);
+        assert!(game.current().unwrap().is_paused());
+
+        clock.advance(Duration::from_secs(60));
+        game.handle(Event::Tick);
+        assert_eq!(game.current().unwrap().points(), MAX_POINTS);
+        // answers are not accepted while the code is hidden
+        assert!(!game.handle(Event::Answer(0)));
+
+        game.handle(Event::Resume);
+        assert!(!game.current().unwrap().is_paused());
+        clock.advance(Duration::from_millis(500));
+        game.handle(Event::Tick);
+        assert_eq!(game.current().unwrap().points(), MAX_POINTS);
+        assert!(game.handle(Event::Answer(0)));
+        let round = &game.rounds()[0];
+        assert_eq!(round.elapsed_ms, 1500);
+        assert_eq!(round.points, MAX_POINTS + 3);
+    }
+
+    #[test]
+    fn losing_the_last_life_ends_the_game() {
+        let (mut game, _) = game(Mode::SuddenDeath);
+        assert!(!game.is_over());
+        game.start_round(code());
+        game.handle(Event::Answer(1));
+        assert_eq!(game.lives(), 0);
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn quitting_ends_the_game_without_costing_a_life() {
+        let (mut game, _) = game(Mode::Classic);
+        game.start_round(code());
+        assert!(game.handle(Event::Quit));
+        assert_eq!(game.rounds()[0].outcome, Outcome::Quit);
+        assert_eq!(game.lives(), 5);
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn seeded_game_plays_out_exactly() {
+        let (mut game, clock) = game(Mode::Classic);
+        let answer_after = |game: &mut Game, steps: usize, answer: usize| {
+            game.start_round(code());
+            for _ in 0..steps {
+                assert!(!step(game, &clock));
+            }
+            assert!(game.handle(Event::Answer(answer)));
+        };
+        answer_after(&mut game, 0, 0);
+        answer_after(&mut game, 3, 0);
+        answer_after(&mut game, 1, 0);
+        game.start_round(code());
+        game.handle(Event::Lifeline(Lifeline::Reveal));
+        game.handle(Event::Answer(3));
+        answer_after(&mut game, 0, 0);
+        game.handle(Event::Quit);
+
+        let rounds: Vec<_> = game
+            .rounds()
+            .iter()
+            .map(|round| {
+                (
+                    round.answer,
+                    round.elapsed_ms,
+                    round.visible_chars,
+                    round.points,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rounds,
+            [
+                (Some(0), 0, 1, 12 + 3),
+                (Some(0), 3 * 2001, 8, 9),
+                (Some(0), 2001, 2, 11 + 3),
+                (Some(3), 0, 2, 0),
+                (Some(0), 0, 1, 12 + 3),
+            ]
+        );
+        assert_eq!(game.rounds()[3].lifelines, [Lifeline::Reveal]);
+        assert_eq!(game.points(), 15 + 9 + 14 + 15);
+        assert_eq!(game.lives(), 4);
+        assert_eq!(game.streak(), 1);
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn same_seed_reveals_and_removes_the_same() {
+        let play = |seed: u64| {
+            let mut game = Game::new(Settings::default()).seed(seed);
+            (0..5)
+                .map(|_| {
+                    game.start_round(Code {
+                        origin: None,
+                        options: (0..8).map(|idx| idx.to_string()).collect(),
+                        ..code()
+                    });
+                    let origin = game.current().unwrap().origin();
+                    game.handle(Event::Lifeline(Lifeline::FiftyFifty));
+                    let removed = game.current().unwrap().removed().clone();
+                    game.handle(Event::Lifeline(Lifeline::Skip));
+                    game.handle(Event::Quit);
+                    (origin, removed)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(play(7), play(7));
+    }
+
+    #[tokio::test]
+    async fn same_seed_serves_the_same_code() {
+        let codes = |seed: u64| async move {
+            let mut provider = PackProvider::new(Pack::load(None).unwrap());
+            provider.seed(seed);
+            let mut codes = Vec::new();
+            for _ in 0..20 {
+                let code = provider.get_code().await.unwrap();
+                codes.push((code.reference, code.options, code.language));
+            }
+            codes
+        };
+        assert_eq!(codes(7).await, codes(7).await);
+    }
+}
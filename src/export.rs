@@ -0,0 +1,369 @@
+use std::io::Write;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::ArgEnum;
+use language_guesser::engine::{Outcome, Round};
+use language_guesser::settings::{Difficulty, Mode, Settings};
+use language_guesser::CodeProviders;
+use serde::Serialize;
+
+use crate::results::{answer_text, score_text, time_text};
+
+/// How the results are printed once the game is over
+#[derive(ArgEnum, Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+    Markdown,
+    Share,
+}
+
+/// A finished game with everything needed to export it
+pub struct Report {
+    pub settings: Settings,
+    pub started_at: DateTime<Local>,
+    pub finished_at: DateTime<Local>,
+    pub points: i32,
+    pub rounds: Vec<Round>,
+    /// The seed of a reproducible game, like the daily challenge
+    pub seed: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct RoundRecord<'a> {
+    round: usize,
+    language: &'a str,
+    answer: Option<&'a str>,
+    outcome: Outcome,
+    points: i32,
+    base: i32,
+    bonus: i32,
+    multiplier: i32,
+    streak: usize,
+    elapsed_ms: u64,
+    visible_chars: usize,
+    reference: &'a str,
+}
+
+impl<'a> RoundRecord<'a> {
+    fn new(idx: usize, round: &'a Round) -> Self {
+        Self {
+            round: idx + 1,
+            language: round.language(),
+            answer: round.answered_language(),
+            outcome: round.outcome,
+            points: round.points,
+            base: round.breakdown.base,
+            bonus: round.breakdown.bonus,
+            multiplier: round.breakdown.multiplier,
+            streak: round.breakdown.streak,
+            elapsed_ms: round.elapsed_ms,
+            visible_chars: round.visible_chars,
+            reference: &round.code.reference,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GameRecord<'a> {
+    started_at: DateTime<Local>,
+    finished_at: DateTime<Local>,
+    mode: Mode,
+    difficulty: Difficulty,
+    provider: CodeProviders,
+    /// Empty if all languages were used
+    languages: &'a [String],
+    options: u8,
+    seed: Option<u64>,
+    points: i32,
+    rounds: Vec<RoundRecord<'a>>,
+}
+
+/// A row of the CSV export, the game is repeated in every row so each can stand on its own
+#[derive(Serialize)]
+struct CsvRecord<'a> {
+    started_at: DateTime<Local>,
+    mode: Mode,
+    difficulty: Difficulty,
+    total_points: i32,
+    round: usize,
+    language: &'a str,
+    answer: Option<&'a str>,
+    outcome: Outcome,
+    points: i32,
+    base: i32,
+    bonus: i32,
+    multiplier: i32,
+    streak: usize,
+    elapsed_ms: u64,
+    visible_chars: usize,
+    reference: &'a str,
+}
+
+/// The points of all rounds split up by where they came from, and the longest streak
+pub fn summary(rounds: &[Round]) -> String {
+    let base: i32 = rounds.iter().map(|round| round.breakdown.base).sum();
+    let bonus: i32 = rounds.iter().map(|round| round.breakdown.bonus).sum();
+    let total: i32 = rounds.iter().map(|round| round.points).sum();
+    let best_streak = rounds
+        .iter()
+        .map(|round| round.breakdown.streak)
+        .max()
+        .unwrap_or_default();
+    let regained = rounds
+        .iter()
+        .filter(|round| round.breakdown.regained_life)
+        .count();
+    let mut summary = format!(
+        "{} base + {} quick bonus + {} from streaks, longest streak {}",
+        base,
+        bonus,
+        total - base - bonus,
+        best_streak
+    );
+    if regained > 0 {
+        summary += &format!(", {} lives regained", regained);
+    }
+    summary
+}
+
+/// Writes every game of a session to `out` in the given `format`, one after another.
+///
+/// A single game is written like [`Report::write`] does. For more games JSON gets an array of them
+/// and CSV a single header, every row tells which game it belongs to.
+pub fn write_all(reports: &[Report], format: Format, out: &mut dyn Write) -> Result<()> {
+    match (format, reports) {
+        (_, [report]) => report.write(format, out)?,
+        (Format::Json, _) => {
+            let records: Vec<_> = reports.iter().map(Report::record).collect();
+            serde_json::to_writer_pretty(&mut *out, &records)?;
+            writeln!(out)?;
+        }
+        (Format::Csv, _) => {
+            let mut writer = csv::Writer::from_writer(out);
+            for report in reports {
+                report.write_csv(&mut writer)?;
+            }
+            writer.flush()?;
+        }
+        _ => {
+            for (idx, report) in reports.iter().enumerate() {
+                if idx > 0 {
+                    writeln!(out)?;
+                }
+                report.write(format, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Report {
+    fn records(&self) -> impl Iterator<Item = RoundRecord<'_>> {
+        self.rounds
+            .iter()
+            .enumerate()
+            .map(|(idx, round)| RoundRecord::new(idx, round))
+    }
+
+    fn record(&self) -> GameRecord<'_> {
+        GameRecord {
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            mode: self.settings.mode,
+            difficulty: self.settings.difficulty,
+            provider: self.settings.provider,
+            languages: &self.settings.languages,
+            options: self.settings.options,
+            seed: self.seed,
+            points: self.points,
+            rounds: self.records().collect(),
+        }
+    }
+
+    /// Writes the game to `out` in the given `format`
+    pub fn write(&self, format: Format, out: &mut dyn Write) -> Result<()> {
+        match format {
+            Format::Table => self.write_table(out)?,
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut *out, &self.record())?;
+                writeln!(out)?;
+            }
+            Format::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+                self.write_csv(&mut writer)?;
+                writer.flush()?;
+            }
+            Format::Markdown => self.write_markdown(out)?,
+            Format::Share => self.write_share(out)?,
+        }
+        Ok(())
+    }
+
+    fn write_csv<W: Write>(&self, writer: &mut csv::Writer<W>) -> Result<()> {
+        for round in self.records() {
+            writer.serialize(CsvRecord {
+                started_at: self.started_at,
+                mode: self.settings.mode,
+                difficulty: self.settings.difficulty,
+                total_points: self.points,
+                round: round.round,
+                language: round.language,
+                answer: round.answer,
+                outcome: round.outcome,
+                points: round.points,
+                base: round.base,
+                bonus: round.bonus,
+                multiplier: round.multiplier,
+                streak: round.streak,
+                elapsed_ms: round.elapsed_ms,
+                visible_chars: round.visible_chars,
+                reference: round.reference,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn write_table(&self, out: &mut dyn Write) -> Result<()> {
+        use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+        use comfy_table::presets::UTF8_FULL;
+        use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
+
+        writeln!(
+            out,
+            "\nYour total points {}!\n{}\n\nDetails:",
+            self.points,
+            summary(&self.rounds)
+        )?;
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(
+                [
+                    "Score",
+                    "Breakdown",
+                    "Language",
+                    "Answer",
+                    "Time",
+                    "Reference",
+                ]
+                .iter()
+                .map(|s| Cell::new(s).set_alignment(CellAlignment::Center)),
+            );
+        for round in &self.rounds {
+            table.add_row(vec![
+                Cell::new(score_text(round)),
+                Cell::new(breakdown(round)),
+                Cell::new(round.language()),
+                Cell::new(answer_text(round)),
+                Cell::new(time_text(round)),
+                Cell::new(&round.code.reference),
+            ]);
+        }
+        writeln!(out, "{}", table)?;
+        Ok(())
+    }
+
+    fn write_markdown(&self, out: &mut dyn Write) -> Result<()> {
+        writeln!(out, "# Language Guesser results\n")?;
+        writeln!(out, "- Date: {}", self.started_at.format("%Y-%m-%d %H:%M"))?;
+        writeln!(
+            out,
+            "- Mode: {}, Difficulty: {}",
+            self.settings.mode.name(),
+            self.settings.difficulty.name()
+        )?;
+        writeln!(
+            out,
+            "- Points: **{}** in {} rounds ({})\n",
+            self.points,
+            self.rounds.len(),
+            summary(&self.rounds)
+        )?;
+        writeln!(
+            out,
+            "| # | Score | Breakdown | Language | Answer | Time | Reference |"
+        )?;
+        writeln!(
+            out,
+            "|--:|------:|-----------|----------|--------|-----:|-----------|"
+        )?;
+        for (idx, round) in self.rounds.iter().enumerate() {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                idx + 1,
+                score_text(round),
+                breakdown(round),
+                escape(round.language()),
+                escape(&answer_text(round)),
+                time_text(round),
+                escape(&round.code.reference)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// A few lines to paste into a chat, revealing how the game went but not the languages
+    fn write_share(&self, out: &mut dyn Write) -> Result<()> {
+        let mut title = match self.settings.mode {
+            Mode::Daily => format!(
+                "Language Guesser daily {}",
+                self.started_at.format("%Y-%m-%d")
+            ),
+            mode => format!(
+                "Language Guesser {} {}",
+                mode.name(),
+                self.settings.difficulty.name()
+            ),
+        };
+        // the date already tells which daily challenge it was
+        if let Some(seed) = self.seed.filter(|_| self.settings.mode != Mode::Daily) {
+            title += &format!(" (seed {})", seed);
+        }
+        writeln!(out, "{}: {} points", title, self.points)?;
+        writeln!(
+            out,
+            "{}",
+            self.rounds.iter().map(square).collect::<String>()
+        )?;
+        let points: Vec<_> = self
+            .rounds
+            .iter()
+            .map(|round| round.points.to_string())
+            .collect();
+        writeln!(out, "{}", points.join(" "))?;
+        Ok(())
+    }
+}
+
+/// A square for every round like `🟩` for a hit and `🟥` for a miss
+fn square(round: &Round) -> &'static str {
+    match round.outcome {
+        Outcome::Guess if round.is_correct() => "🟩",
+        Outcome::Guess => "🟥",
+        Outcome::Timeout => "⬛",
+        Outcome::Skip => "🟨",
+        Outcome::Quit => "⬜",
+        Outcome::Beaten => "🟦",
+    }
+}
+
+fn breakdown(round: &Round) -> String {
+    if round.is_correct() {
+        round.breakdown.to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Escapes characters that would end a markdown table cell, line breaks become spaces
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", " ")
+        .replace(&['\n', '\r'][..], " ")
+}
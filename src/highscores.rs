@@ -0,0 +1,106 @@
+use std::fs;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use language_guesser::settings::{Difficulty, Mode, Scoring, Settings};
+use serde::{Deserialize, Serialize};
+
+const HIGHSCORES_FILE: &str = "highscores.yml";
+/// How many scores are kept for every board
+const KEEP: usize = 10;
+/// How many scores are shown on the menu and the results screen
+pub const TOP: usize = 5;
+
+/// The settings that make scores comparable, every combination has its own high scores
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Board {
+    pub mode: Mode,
+    pub difficulty: Difficulty,
+    pub options: u8,
+    /// Sorted, empty if all languages were used
+    pub languages: Vec<String>,
+    pub scoring: Scoring,
+}
+
+impl Board {
+    pub fn new(settings: &Settings) -> Self {
+        // the daily challenge is played with its own settings, whatever is chosen in the menu
+        let daily;
+        let settings = if settings.mode == Mode::Daily {
+            daily = settings.daily();
+            &daily
+        } else {
+            settings
+        };
+        let mut languages = settings.languages.clone();
+        languages.sort();
+        Self {
+            mode: settings.mode,
+            difficulty: settings.difficulty,
+            options: settings.options,
+            languages,
+            scoring: settings.scoring.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HighScore {
+    #[serde(flatten)]
+    pub board: Board,
+    pub points: i32,
+    pub rounds: usize,
+    pub date: DateTime<Local>,
+}
+
+/// The best scores of all games played on this machine
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct HighScores {
+    scores: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub fn load() -> Result<Self> {
+        let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+        Ok(match dirs.find_data_file(HIGHSCORES_FILE) {
+            Some(path) => serde_yaml::from_slice(&fs::read(path)?)?,
+            None => HighScores::default(),
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+        fs::write(
+            dirs.place_data_file(HIGHSCORES_FILE)?,
+            serde_yaml::to_string(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// The best scores on `board`, highest first
+    pub fn top(&self, board: &Board) -> Vec<&HighScore> {
+        let mut scores: Vec<_> = self.scores.iter().filter(|s| &s.board == board).collect();
+        // the older score stays ahead on a tie
+        scores.sort_by(|a, b| b.points.cmp(&a.points).then(a.date.cmp(&b.date)));
+        scores
+    }
+
+    /// Adds a score, dropping the lowest ones of its board if there are too many.
+    ///
+    /// Returns whether it is a new personal best.
+    pub fn add(&mut self, score: HighScore) -> bool {
+        let best = score.points > 0
+            && self
+                .top(&score.board)
+                .first()
+                .is_none_or(|best| score.points > best.points);
+        let board = score.board.clone();
+        self.scores.push(score);
+
+        let dropped: Vec<_> = self.top(&board).iter().skip(KEEP).map(|s| s.date).collect();
+        self.scores
+            .retain(|s| s.board != board || !dropped.contains(&s.date));
+        best
+    }
+}
@@ -0,0 +1,73 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Outcome, Round};
+use crate::settings::{Difficulty, Mode, Settings};
+
+/// Every round ever played, one JSON object per line so a game only needs to be appended
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// A round played in the past, without the code to keep the history small
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Entry {
+    /// When the game the round was part of started
+    pub date: DateTime<Local>,
+    pub mode: Mode,
+    pub difficulty: Difficulty,
+    pub language: String,
+    pub answer: Option<String>,
+    /// The languages the player could choose from
+    pub options: Vec<String>,
+    pub outcome: Outcome,
+    pub points: i32,
+    pub elapsed_ms: u64,
+    pub visible_chars: usize,
+}
+
+impl Entry {
+    pub fn is_correct(&self) -> bool {
+        self.answer.as_ref() == Some(&self.language)
+    }
+}
+
+/// Loads all rounds played so far, oldest first
+pub fn load() -> Result<Vec<Entry>> {
+    let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+    match dirs.find_data_file(HISTORY_FILE) {
+        Some(path) => fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Adds the `rounds` of a game started at `date` to the history
+pub fn append(date: DateTime<Local>, settings: &Settings, rounds: &[Round]) -> Result<()> {
+    let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dirs.place_data_file(HISTORY_FILE)?)?;
+    for round in rounds {
+        let entry = Entry {
+            date,
+            mode: settings.mode,
+            difficulty: settings.difficulty,
+            language: round.language().to_string(),
+            answer: round.answered_language().map(String::from),
+            options: round.code.options.clone(),
+            outcome: round.outcome,
+            points: round.points,
+            elapsed_ms: round.elapsed_ms,
+            visible_chars: round.visible_chars,
+        };
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,398 @@
+use std::num::NonZeroU8;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use crossterm::event::{
+    self, Event, EventStream, KeyCode as Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use futures_util::StreamExt;
+use language_guesser::engine::{Event as GameEvent, Game, Round};
+use language_guesser::party::{Party, MAX_PARTY_OPTIONS, MAX_PLAYERS};
+use language_guesser::settings::Settings;
+use language_guesser::CodeProvider;
+use tokio::time::{self, MissedTickBehavior};
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use tui::{Frame, Terminal};
+
+use crate::results::answer_text;
+use crate::ui::{contains, draw_code, options_width};
+
+/// The keys of every player, the first one picks the first option and so on. They are spread
+/// over the keyboard so the players do not get in each other's way.
+const PLAYER_KEYS: [[char; MAX_PARTY_OPTIONS]; MAX_PLAYERS] = [
+    ['1', '2', '3', '4'],
+    ['7', '8', '9', '0'],
+    ['z', 'x', 'c', 'v'],
+    ['m', ',', '.', '/'],
+];
+
+/// The color every player is shown in
+const PLAYER_COLORS: [Color; MAX_PLAYERS] = [Color::Red, Color::Blue, Color::Green, Color::Yellow];
+
+/// The player pressing `key` and the option they chose with it
+fn player_key(key: char) -> Option<(usize, usize)> {
+    PLAYER_KEYS.iter().enumerate().find_map(|(player, keys)| {
+        keys.iter()
+            .position(|&other| other == key)
+            .map(|option| (player, option))
+    })
+}
+
+fn player_name(player: usize) -> Span<'static> {
+    Span::styled(
+        format!("Player {}", player + 1),
+        Style::default()
+            .fg(PLAYER_COLORS[player])
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// How a round went for a player, the points if they were right
+fn round_cell(round: &Round) -> Cell<'static> {
+    if round.is_correct() {
+        Cell::from(format!("✔ {}", round.points)).style(Style::default().fg(Color::Green))
+    } else if round.is_missed() {
+        Cell::from(format!("✘ {}", answer_text(round))).style(Style::default().fg(Color::Red))
+    } else {
+        Cell::from(answer_text(round))
+    }
+}
+
+/// Draws the round of `party` that is being played.
+///
+/// Returns where the code and the button continuing a paused round were drawn to map mouse events
+/// to them.
+fn draw<B: Backend>(f: &mut Frame<B>, party: &Party, code_scroll: &mut u16) -> (Rect, Rect) {
+    let current = party.current().expect("a round is being played");
+    let players = party.players();
+    let options = &current.code().options;
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(players.len() as u16 + 5),
+            Constraint::Min(0),
+        ])
+        .split(f.size());
+    let block = Block::default().borders(Borders::ALL);
+    let inner = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(block.inner(vertical[0]));
+    let help = format!(
+        "Round Points: {}. The first correct answer scores, a wrong one costs a life.\n\
+        Press CTRL+C if you want to give up, Space or Esc to pause.",
+        party.round_points()
+    );
+    f.render_widget(Paragraph::new(help), inner[0]);
+    let rows = players.iter().enumerate().map(|(player, game)| {
+        let status = if game.current().is_some() {
+            Cell::from("guessing...")
+        } else if party.in_round(player) {
+            round_cell(game.rounds().last().expect("the round ended"))
+        } else {
+            Cell::from("out")
+        };
+        Row::new(vec![
+            Cell::from(player_name(player)),
+            Cell::from(
+                PLAYER_KEYS[player][..options.len()]
+                    .iter()
+                    .map(char::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            Cell::from(game.points().to_string()),
+            Cell::from("🫀".repeat(game.lives())),
+            status,
+        ])
+    });
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec!["", "Keys", "Points", "Lives", "This round"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .widths(&[
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Min(0),
+        ]);
+    f.render_widget(table, inner[1]);
+    f.render_widget(block, vertical[0]);
+
+    let keys_width = 2 * players.len() as u16;
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(
+                (keys_width + options_width(options))
+                    .max(20)
+                    .min(vertical[1].width / 3),
+            ),
+            Constraint::Min(0),
+        ])
+        .split(vertical[1]);
+    let rows = options.iter().enumerate().map(|(idx, option)| {
+        let mut spans: Vec<_> = (0..players.len())
+            .map(|player| {
+                Span::styled(
+                    format!("{} ", PLAYER_KEYS[player][idx]),
+                    Style::default().fg(PLAYER_COLORS[player]),
+                )
+            })
+            .collect();
+        spans.push(Span::raw(option.as_str()));
+        Row::new(vec![Cell::from(Spans::from(spans))])
+    });
+    let table = Table::new(rows)
+        .widths(&[Constraint::Percentage(100)])
+        .block(Block::default().title("Options").borders(Borders::ALL));
+    f.render_widget(table, horizontal[0]);
+    let resume = draw_code(f, current, horizontal[1], code_scroll, "Space or Esc");
+    (horizontal[1], resume)
+}
+
+/// Plays a game for every player of `settings` on one screen until all of them ran out of lives
+/// or they give up, reproducibly if there is a `seed`.
+///
+/// The screen is redrawn at most `fps` times per second and only if something on it changed.
+pub async fn play<B: Backend>(
+    terminal: &mut Terminal<B>,
+    settings: &Settings,
+    seed: Option<u64>,
+    code_provider: Arc<Box<dyn CodeProvider>>,
+    fps: NonZeroU8,
+) -> Result<Party> {
+    let frame = Duration::from_secs(1) / fps.get().into();
+    let mut party = Party::new(settings.clone(), settings.players.into());
+    if let Some(seed) = seed {
+        party = party.seed(seed);
+    }
+    let c = code_provider.clone();
+    let mut next = Box::pin(tokio::spawn(async move { c.get_code().await }));
+    while !party.is_over() {
+        let code = next.await??;
+        let c = code_provider.clone();
+        next = Box::pin(tokio::spawn(async move { c.get_code().await }));
+        let mut code_scroll: u16 = 0;
+        let mut code_area = Rect::default();
+        let mut resume_area = Rect::default();
+        party.start_round(code);
+        let mut events = EventStream::new();
+        let mut frames = time::interval(frame);
+        frames.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut drawn_points = None;
+        let mut changed = true;
+        loop {
+            let event = tokio::select! {
+                _ = frames.tick() => {
+                    if party.handle(GameEvent::Tick) {
+                        break;
+                    }
+                    let points = Some(party.round_points());
+                    if changed || points != drawn_points {
+                        terminal.draw(|f| {
+                            let (code, resume) = draw(f, &party, &mut code_scroll);
+                            code_area = code;
+                            resume_area = resume;
+                        })?;
+                        drawn_points = points;
+                        changed = false;
+                    }
+                    continue;
+                }
+                event = events.next() => match event {
+                    Some(event) => event?,
+                    None => bail!("The terminal stopped sending input!"),
+                },
+            };
+            // moving the mouse changes nothing on the screen, everything else might
+            if let Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Moved,
+                ..
+            }) = event
+            {
+                continue;
+            }
+            changed = true;
+
+            let ended = match event {
+                Event::Key(KeyEvent {
+                    code: Key::Char('c'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                }) => party.handle(GameEvent::Quit),
+                Event::FocusLost => party.handle(GameEvent::Pause),
+                Event::Key(KeyEvent {
+                    code: Key::Esc | Key::Char(' '),
+                    ..
+                }) => match party.current() {
+                    Some(current) => party.handle(current.toggle_pause()),
+                    None => false,
+                },
+                Event::Key(KeyEvent {
+                    code: Key::Char(key),
+                    ..
+                }) => match player_key(key) {
+                    Some((player, option)) if player < party.players().len() => {
+                        party.answer(player, option)
+                    }
+                    _ => false,
+                },
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                }) if contains(resume_area, column, row) => party.handle(GameEvent::Resume),
+                Event::Mouse(MouseEvent {
+                    kind, column, row, ..
+                }) if contains(code_area, column, row) => {
+                    match kind {
+                        MouseEventKind::ScrollUp => code_scroll = code_scroll.saturating_sub(1),
+                        MouseEventKind::ScrollDown => code_scroll += 1,
+                        _ => {}
+                    }
+                    false
+                }
+                _ => false,
+            };
+            if ended {
+                break;
+            }
+        }
+    }
+    Ok(party)
+}
+
+/// Shows the rounds of every player next to each other and who won.
+///
+/// Returns whether the players want to play another game.
+pub fn show<B: Backend>(terminal: &mut Terminal<B>, party: &Party) -> Result<bool> {
+    let players = party.players();
+    // players only drop out, so the one that played the longest took part in every round
+    let rounds = players
+        .iter()
+        .map(Game::rounds)
+        .max_by_key(|rounds| rounds.len())
+        .unwrap_or_default();
+    let best = players.iter().map(Game::points).max().unwrap_or_default();
+    let winners: Vec<_> = (0..players.len())
+        .filter(|&player| players[player].points() == best)
+        .collect();
+    let mut offset = 0;
+    loop {
+        let mut visible = 0;
+        terminal.draw(|f| {
+            let vertical = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(players.len() as u16 + 3),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                ])
+                .split(f.size());
+            let mut lines = vec![Spans::from(match winners.as_slice() {
+                [winner] => vec![
+                    player_name(*winner),
+                    Span::raw(format!(" wins with {} points!", best)),
+                ],
+                _ => {
+                    let mut spans = vec![Span::raw("A tie between ")];
+                    for (idx, &winner) in winners.iter().enumerate() {
+                        if idx > 0 {
+                            spans.push(Span::raw(" and "));
+                        }
+                        spans.push(player_name(winner));
+                    }
+                    spans.push(Span::raw(format!(" with {} points!", best)));
+                    spans
+                }
+            })];
+            lines.extend(players.iter().enumerate().map(|(player, game)| {
+                let first = game
+                    .rounds()
+                    .iter()
+                    .filter(|round| round.is_correct())
+                    .count();
+                Spans::from(vec![
+                    player_name(player),
+                    Span::raw(format!(
+                        ": {} points, first in {} of {} rounds, {} lives left",
+                        game.points(),
+                        first,
+                        game.rounds().len(),
+                        game.lives()
+                    )),
+                ])
+            }));
+            let header = Paragraph::new(lines)
+                .block(Block::default().title("Results").borders(Borders::ALL));
+            f.render_widget(header, vertical[0]);
+
+            let block = Block::default().title("Rounds").borders(Borders::ALL);
+            // the header takes up the first row
+            visible = block.inner(vertical[1]).height.saturating_sub(1) as usize;
+            offset = offset.min(rounds.len().saturating_sub(visible));
+            let rows = rounds
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(visible)
+                .map(|(idx, round)| {
+                    let mut cells = vec![
+                        Cell::from((idx + 1).to_string()),
+                        Cell::from(round.language().to_string()),
+                    ];
+                    cells.extend(players.iter().map(|game| match game.rounds().get(idx) {
+                        Some(round) => round_cell(round),
+                        None => Cell::from(""),
+                    }));
+                    Row::new(cells)
+                });
+            let mut header = vec![Cell::from("#"), Cell::from("Language")];
+            header.extend((0..players.len()).map(|player| Cell::from(player_name(player))));
+            let mut widths = vec![Constraint::Length(3), Constraint::Length(20)];
+            widths.extend((0..players.len()).map(|_| Constraint::Length(20)));
+            let table = Table::new(rows)
+                .header(Row::new(header).style(Style::default().add_modifier(Modifier::BOLD)))
+                .widths(&widths)
+                .block(block);
+            f.render_widget(table, vertical[1]);
+
+            let help = "↑/↓ scroll, n new game, q quit";
+            f.render_widget(Paragraph::new(help), vertical[2]);
+        })?;
+
+        match event::read()? {
+            Event::Key(KeyEvent {
+                code: key,
+                modifiers,
+                ..
+            }) => match (key, modifiers) {
+                (Key::Char('c'), KeyModifiers::CONTROL) | (Key::Char('q'), _) | (Key::Esc, _) => {
+                    return Ok(false)
+                }
+                (Key::Char('n'), _) => return Ok(true),
+                (Key::Up, _) | (Key::Char('k'), _) => offset = offset.saturating_sub(1),
+                (Key::Down, _) | (Key::Char('j'), _) => offset += 1,
+                (Key::PageUp, _) => offset = offset.saturating_sub(visible),
+                (Key::PageDown, _) => offset += visible,
+                _ => {}
+            },
+            Event::Mouse(MouseEvent { kind, .. }) => match kind {
+                MouseEventKind::ScrollUp => offset = offset.saturating_sub(1),
+                MouseEventKind::ScrollDown => offset += 1,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
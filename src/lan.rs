@@ -0,0 +1,704 @@
+use std::num::NonZeroU8;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use crossterm::event::{
+    Event, EventStream, KeyCode as Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use futures_util::{future, StreamExt};
+use language_guesser::engine::{Event as GameEvent, Game};
+use language_guesser::net::{self, ClientMessage, ServerMessage, Standing, Status};
+use language_guesser::party::Party;
+use language_guesser::settings::{Mode, Settings};
+use language_guesser::CodeProvider;
+use tokio::io::BufReader;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::{self, MissedTickBehavior};
+use tui::backend::Backend;
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Modifier, Style};
+use tui::widgets::{Block, Borders, Paragraph, Row, Table, Wrap};
+use tui::{Frame, Terminal};
+
+use crate::ui::{contains, draw_code, options_width, OptionsState, HOTKEYS};
+
+/// How often the host checks whether the time of a round ran out
+const TICK: Duration = Duration::from_millis(50);
+/// How long the result of a round is shown before the next one starts
+const BETWEEN_ROUNDS: Duration = Duration::from_secs(3);
+/// How long a new connection has to ask to join
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a player may take to receive a message before they are disconnected, so a stuck
+/// connection does not hold up everyone else
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The connection to every player, `None` once it was closed
+pub type Writers = Vec<Option<OwnedWriteHalf>>;
+
+/// A new connection that asked to join, with the name the player chose
+pub struct Joined {
+    pub name: String,
+    reader: BufReader<OwnedReadHalf>,
+    pub writer: OwnedWriteHalf,
+}
+
+/// Waits for the first message of `stream`, `None` if the connection did not ask to join in
+/// [`HANDSHAKE_TIMEOUT`]
+pub async fn handshake(stream: TcpStream) -> Option<Joined> {
+    let (reader, writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    match time::timeout(HANDSHAKE_TIMEOUT, net::receive(&mut reader)).await {
+        Ok(Ok(Some(ClientMessage::Join { name }))) => Some(Joined {
+            name,
+            reader,
+            writer,
+        }),
+        _ => None,
+    }
+}
+
+/// Passes on every message of `joined`, who is the player with the index `player`, until they
+/// leave
+pub fn forward(
+    player: usize,
+    joined: Joined,
+    sender: mpsc::UnboundedSender<(usize, Option<ClientMessage>)>,
+) -> OwnedWriteHalf {
+    let mut reader = joined.reader;
+    tokio::spawn(async move {
+        loop {
+            // anything unreadable ends the connection like closing it
+            let message = net::receive(&mut reader).await.ok().flatten();
+            let left = matches!(message, None | Some(ClientMessage::Quit));
+            if sender.send((player, message)).is_err() || left {
+                break;
+            }
+        }
+    });
+    joined.writer
+}
+
+/// Rejects games the players can not play together
+pub fn validate(settings: &Settings) -> Result<()> {
+    if matches!(settings.mode, Mode::Learning | Mode::Daily) {
+        bail!("{} can only be played alone!", settings.mode.name());
+    }
+    Ok(())
+}
+
+/// Sends `message` to every player that is still connected, to all of them at once
+pub async fn broadcast(writers: &mut Writers, message: &ServerMessage) {
+    let sends = writers.iter_mut().map(|writer| async move {
+        if let Some(connection) = writer {
+            if !matches!(
+                time::timeout(SEND_TIMEOUT, net::send(connection, message)).await,
+                Ok(Ok(()))
+            ) {
+                // the reader of the connection notices as well and lets the player leave
+                *writer = None;
+            }
+        }
+    });
+    future::join_all(sends).await;
+}
+
+/// Prints the players ordered by their points
+pub fn print_standings(standings: &[Standing]) {
+    let mut standings: Vec<_> = standings.iter().collect();
+    standings.sort_by_key(|standing| -standing.points);
+    for (idx, standing) in standings.iter().enumerate() {
+        println!("{}. {} {} points", idx + 1, standing.name, standing.points);
+    }
+}
+
+/// Hosts a game for `players` players joining on `port`, playing with `settings` and the code of
+/// `code_provider`. The host only keeps the score, everyone plays with [`join`].
+///
+/// Every round all players get the same code, answers are timed when they arrive at the host. The
+/// progress is printed as the game goes on.
+pub async fn host(
+    settings: Settings,
+    seed: Option<u64>,
+    code_provider: Arc<Box<dyn CodeProvider>>,
+    port: u16,
+    players: usize,
+) -> Result<()> {
+    validate(&settings)?;
+    if players == 0 {
+        bail!("At least one player is needed!");
+    }
+    let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    host_on(listener, settings, seed, code_provider, players).await
+}
+
+/// Hosts the game of [`host`] for the players connecting to `listener`
+async fn host_on(
+    listener: TcpListener,
+    settings: Settings,
+    seed: Option<u64>,
+    code_provider: Arc<Box<dyn CodeProvider>>,
+    players: usize,
+) -> Result<()> {
+    println!(
+        "Waiting for {} players, join with `{} join <this machine>:{}`",
+        players,
+        env!("CARGO_PKG_NAME"),
+        listener.local_addr()?.port()
+    );
+    // every connection is greeted on its own so one that does not say anything holds up nobody
+    let (join_sender, mut joins) = mpsc::unbounded_channel();
+    let accept = tokio::spawn(async move {
+        while let Ok((stream, address)) = listener.accept().await {
+            let sender = join_sender.clone();
+            tokio::spawn(async move {
+                match handshake(stream).await {
+                    Some(joined) => {
+                        sender.send((address, joined)).ok();
+                    }
+                    None => println!("{} did not join the game, ignoring it", address),
+                }
+            });
+        }
+    });
+    let (sender, mut messages) = mpsc::unbounded_channel();
+    let mut names = Vec::new();
+    let mut writers = Writers::new();
+    while names.len() < players {
+        let (address, mut joined) = match joins.recv().await {
+            Some(joined) => joined,
+            None => bail!("No more players can join!"),
+        };
+        let player = names.len();
+        let welcome = ServerMessage::Welcome {
+            player,
+            settings: settings.clone(),
+        };
+        if net::send(&mut joined.writer, &welcome).await.is_err() {
+            continue;
+        }
+        println!(
+            "{} joined from {} ({}/{})",
+            joined.name,
+            address,
+            player + 1,
+            players
+        );
+        names.push(joined.name.clone());
+        writers.push(Some(forward(player, joined, sender.clone())));
+    }
+    accept.abort();
+    // only the connections send messages, once all of them are closed the game ends
+    drop(sender);
+
+    let mut party = Party::new(settings, players);
+    if let Some(seed) = seed {
+        party = party.seed(seed);
+    }
+    let c = code_provider.clone();
+    let mut next = Box::pin(tokio::spawn(async move { c.get_code().await }));
+    let mut ticks = time::interval(TICK);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut number = 0;
+    while !party.is_over() {
+        let code = next.await??;
+        let c = code_provider.clone();
+        next = Box::pin(tokio::spawn(async move { c.get_code().await }));
+        party.start_round(code);
+        number += 1;
+        let code = party
+            .current()
+            .expect("a player is still in the game")
+            .code()
+            .clone();
+        let language = code.options[code.language].clone();
+        broadcast(&mut writers, &ServerMessage::Round { number, code }).await;
+        let standings = net::standings(&party, &names);
+        broadcast(&mut writers, &ServerMessage::Scoreboard { standings }).await;
+        loop {
+            let ended = tokio::select! {
+                _ = ticks.tick() => party.handle(GameEvent::Tick),
+                message = messages.recv() => {
+                    let ended = match message {
+                        Some((player, Some(ClientMessage::Answer { round, answer }))) => {
+                            round == number && party.answer(player, answer)
+                        }
+                        Some((_, Some(ClientMessage::Join { .. }))) => false,
+                        Some((player, Some(ClientMessage::Quit))) | Some((player, None)) => {
+                            println!("{} left the game", names[player]);
+                            party.leave(player)
+                        }
+                        None => party.handle(GameEvent::Quit),
+                    };
+                    let standings = net::standings(&party, &names);
+                    broadcast(&mut writers, &ServerMessage::Scoreboard { standings }).await;
+                    ended
+                }
+            };
+            if ended {
+                break;
+            }
+        }
+
+        let standings = net::standings(&party, &names);
+        let results: Vec<_> = standings
+            .iter()
+            .filter(|standing| standing.status != Status::Out)
+            .map(|standing| format!("{} {}", standing.name, standing.status))
+            .collect();
+        if !results.is_empty() {
+            println!("Round {} ({}): {}", number, language, results.join(", "));
+        }
+        broadcast(&mut writers, &ServerMessage::RoundOver { standings }).await;
+        if !party.is_over() {
+            time::sleep(BETWEEN_ROUNDS).await;
+        }
+    }
+
+    let standings = net::standings(&party, &names);
+    broadcast(
+        &mut writers,
+        &ServerMessage::GameOver {
+            standings: standings.clone(),
+        },
+    )
+    .await;
+    print_standings(&standings);
+    Ok(())
+}
+
+/// A player of a hosted game
+struct Client {
+    player: usize,
+    settings: Settings,
+    /// The number of the round being played, 0 before the first one
+    number: usize,
+    /// Reveals the code of the round, whether an answer is right is decided by the host
+    game: Option<Game>,
+    answered: bool,
+    standings: Vec<Standing>,
+    /// The language of the last round once it is over
+    solution: Option<String>,
+    options_state: OptionsState,
+    code_scroll: u16,
+}
+
+impl Client {
+    fn is_out(&self) -> bool {
+        matches!(
+            self.standings.get(self.player),
+            Some(Standing {
+                status: Status::Out,
+                ..
+            })
+        )
+    }
+
+    fn help(&self) -> String {
+        let state = if self.is_out() {
+            String::from("You are out, watching the others.")
+        } else if let Some(solution) = &self.solution {
+            format!("It was {}, the next round starts soon.", solution)
+        } else if self.number == 0 {
+            String::from("Waiting for the other players...")
+        } else if self.answered {
+            String::from("Waiting for the others to answer...")
+        } else {
+            String::from("Press the key next to a language or select it with ↑/↓ (k/j) and Enter.")
+        };
+        format!("{}\nPress CTRL+C if you want to give up.", state)
+    }
+
+    /// Draws the standings and the round being played.
+    ///
+    /// Returns where the options and the code were drawn to map mouse events to them.
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>) -> (Rect, Rect) {
+        let vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(self.standings.len() as u16 + 5),
+                Constraint::Min(0),
+            ])
+            .split(f.size());
+        let block = Block::default()
+            .title(format!("Round {}", self.number))
+            .borders(Borders::ALL);
+        let inner = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(0)])
+            .split(block.inner(vertical[0]));
+        f.render_widget(Paragraph::new(self.help()), inner[0]);
+        let rows = self.standings.iter().enumerate().map(|(idx, standing)| {
+            let row = Row::new(vec![
+                standing.name.clone(),
+                standing.points.to_string(),
+                "🫀".repeat(standing.lives),
+                standing.status.to_string(),
+            ]);
+            if idx == self.player {
+                row.style(Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                row
+            }
+        });
+        let table = Table::new(rows)
+            .header(
+                Row::new(vec!["Player", "Points", "Lives", "This round"])
+                    .style(Style::default().add_modifier(Modifier::REVERSED)),
+            )
+            .widths(&[
+                Constraint::Length(20),
+                Constraint::Length(7),
+                Constraint::Length(12),
+                Constraint::Min(0),
+            ]);
+        f.render_widget(table, inner[1]);
+        f.render_widget(block, vertical[0]);
+
+        let current = match self.game.as_ref().and_then(Game::current) {
+            Some(current) => current,
+            None => {
+                let text = match &self.solution {
+                    Some(_) => "\nThe round is over.",
+                    None if self.number == 0 => "\nThe game starts once everyone joined.",
+                    None => "\nThe time is up.",
+                };
+                let paragraph = Paragraph::new(text)
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: false })
+                    .block(Block::default().borders(Borders::ALL));
+                f.render_widget(paragraph, vertical[1]);
+                return (Rect::default(), Rect::default());
+            }
+        };
+        let options = &current.code().options;
+        let horizontal = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(options_width(options).max(20).min(vertical[1].width / 3)),
+                Constraint::Min(0),
+            ])
+            .split(vertical[1]);
+        let table = self
+            .options_state
+            .table(options, current.removed(), horizontal[0].height);
+        f.render_widget(table, horizontal[0]);
+        draw_code(f, current, horizontal[1], &mut self.code_scroll, "");
+        (horizontal[0], horizontal[1])
+    }
+
+    /// Applies what the host sent, returns the final standings once the game is over
+    fn receive(&mut self, message: ServerMessage) -> Option<Vec<Standing>> {
+        match message {
+            ServerMessage::Welcome { .. } => {}
+            ServerMessage::Round { number, code } => {
+                let mut game = Game::new(self.settings.clone());
+                game.start_round(code);
+                self.game = Some(game);
+                self.number = number;
+                self.answered = false;
+                self.solution = None;
+                self.options_state = OptionsState::default();
+                self.code_scroll = 0;
+            }
+            ServerMessage::Scoreboard { standings } => self.standings = standings,
+            ServerMessage::RoundOver { standings } => {
+                self.standings = standings;
+                self.solution = self
+                    .game
+                    .take()
+                    .and_then(|game| match game.current() {
+                        Some(current) => Some(current.code().clone()),
+                        None => game.rounds().last().map(|round| round.code.clone()),
+                    })
+                    .map(|code| code.options[code.language].clone());
+            }
+            ServerMessage::GameOver { standings } => return Some(standings),
+        }
+        None
+    }
+}
+
+/// Joins the game hosted at `address` as `name`, showing every round until the game is over or
+/// the player gives up.
+///
+/// Returns the standings at the end of the game. The screen is redrawn at most `fps` times per
+/// second and only if something on it changed.
+pub async fn join<B: Backend>(
+    terminal: &mut Terminal<B>,
+    address: &str,
+    name: String,
+    fps: NonZeroU8,
+) -> Result<Vec<Standing>> {
+    let stream = if address.contains(':') {
+        TcpStream::connect(address).await?
+    } else {
+        TcpStream::connect((address, net::DEFAULT_PORT)).await?
+    };
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    net::send(&mut writer, &ClientMessage::Join { name }).await?;
+    let (player, settings) = match net::receive(&mut reader).await? {
+        Some(ServerMessage::Welcome { player, settings }) => (player, settings),
+        _ => bail!("{} did not let us join!", address),
+    };
+    // reading a message can not be interrupted halfway, so it is done on its own
+    let (sender, mut messages) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok(Some(message)) = net::receive(&mut reader).await {
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut client = Client {
+        player,
+        settings,
+        number: 0,
+        game: None,
+        answered: false,
+        standings: Vec::new(),
+        solution: None,
+        options_state: OptionsState::default(),
+        code_scroll: 0,
+    };
+    let mut options_area = Rect::default();
+    let mut code_area = Rect::default();
+    let mut events = EventStream::new();
+    let mut frames = time::interval(Duration::from_secs(1) / fps.get().into());
+    frames.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut drawn_points = None;
+    let mut changed = true;
+    loop {
+        let event = tokio::select! {
+            _ = frames.tick() => {
+                if let Some(game) = &mut client.game {
+                    // the host ends the round for everyone, until then the code stays visible
+                    changed |= game.handle(GameEvent::Tick);
+                }
+                let points = client.game.as_ref().map(Game::round_points);
+                if changed || points != drawn_points {
+                    terminal.draw(|f| {
+                        let (options, code) = client.draw(f);
+                        options_area = options;
+                        code_area = code;
+                    })?;
+                    drawn_points = points;
+                    changed = false;
+                }
+                continue;
+            }
+            message = messages.recv() => {
+                changed = true;
+                match message {
+                    Some(message) => match client.receive(message) {
+                        Some(standings) => return Ok(standings),
+                        None => continue,
+                    },
+                    None => bail!("The connection to the host was lost!"),
+                }
+            }
+            event = events.next() => match event {
+                Some(event) => event?,
+                None => bail!("The terminal stopped sending input!"),
+            },
+        };
+        // moving the mouse changes nothing on the screen, everything else might
+        if let Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Moved,
+            ..
+        }) = event
+        {
+            continue;
+        }
+        changed = true;
+
+        let current = match client.game.as_ref().and_then(Game::current) {
+            Some(current) => current,
+            None => {
+                if let Event::Key(KeyEvent {
+                    code: Key::Char('c'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                }) = event
+                {
+                    net::send(&mut writer, &ClientMessage::Quit).await?;
+                    return Ok(client.standings);
+                }
+                continue;
+            }
+        };
+        let options = &current.code().options;
+        let answer = match event {
+            Event::Key(KeyEvent {
+                code: key,
+                modifiers,
+                ..
+            }) => match (key, modifiers) {
+                (Key::Char('c'), KeyModifiers::CONTROL) => {
+                    net::send(&mut writer, &ClientMessage::Quit).await?;
+                    return Ok(client.standings);
+                }
+                (Key::Up, _) | (Key::Char('k'), _) => {
+                    client
+                        .options_state
+                        .select_previous(options.len(), current.removed());
+                    None
+                }
+                (Key::Down, _) | (Key::Char('j'), _) => {
+                    client
+                        .options_state
+                        .select_next(options.len(), current.removed());
+                    None
+                }
+                (Key::Enter, _) => client.options_state.selected(),
+                (Key::Char(char), _) => HOTKEYS.iter().position(|&hotkey| hotkey == char),
+                _ => None,
+            },
+            Event::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) => match kind {
+                MouseEventKind::Down(MouseButton::Left) => {
+                    client
+                        .options_state
+                        .option_at(options_area, options.len(), column, row)
+                }
+                MouseEventKind::ScrollUp if contains(code_area, column, row) => {
+                    client.code_scroll = client.code_scroll.saturating_sub(1);
+                    None
+                }
+                MouseEventKind::ScrollDown if contains(code_area, column, row) => {
+                    client.code_scroll += 1;
+                    None
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(answer) = answer.filter(|&answer| answer < options.len()) {
+            if !client.answered && !client.is_out() {
+                client.answered = true;
+                let round = client.number;
+                net::send(&mut writer, &ClientMessage::Answer { round, answer }).await?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language_guesser::providers::pack::{Pack, PackProvider};
+
+    /// A player talking to the host directly
+    struct Player {
+        reader: BufReader<OwnedReadHalf>,
+        writer: OwnedWriteHalf,
+    }
+
+    impl Player {
+        async fn join(address: std::net::SocketAddr, name: &str) -> (Self, usize) {
+            let (reader, writer) = TcpStream::connect(address).await.unwrap().into_split();
+            let mut player = Player {
+                reader: BufReader::new(reader),
+                writer,
+            };
+            player.send(ClientMessage::Join { name: name.into() }).await;
+            match player.receive().await {
+                ServerMessage::Welcome { player: idx, .. } => (player, idx),
+                message => panic!("expected a welcome, got {:?}", message),
+            }
+        }
+
+        async fn send(&mut self, message: ClientMessage) {
+            net::send(&mut self.writer, &message).await.unwrap();
+        }
+
+        async fn receive(&mut self) -> ServerMessage {
+            net::receive(&mut self.reader).await.unwrap().unwrap()
+        }
+
+        /// Skips the scoreboards until the end of the round or the game
+        async fn until_over(&mut self) -> ServerMessage {
+            loop {
+                match self.receive().await {
+                    ServerMessage::Scoreboard { .. } => {}
+                    message => return message,
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn two_players_play_a_round() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let provider: Box<dyn CodeProvider> =
+            Box::new(PackProvider::new(Pack::load(None).unwrap()));
+        let host = tokio::spawn(host_on(
+            listener,
+            Settings::default(),
+            Some(1),
+            Arc::new(provider),
+            2,
+        ));
+
+        // a connection that never says anything does not keep the others from joining
+        let _silent = TcpStream::connect(address).await.unwrap();
+        let (mut alice, first) = Player::join(address, "Alice").await;
+        let (mut bob, second) = Player::join(address, "Bob").await;
+        assert_eq!((first, second), (0, 1));
+
+        let code = match alice.receive().await {
+            ServerMessage::Round { number: 1, code } => code,
+            message => panic!("expected the first round, got {:?}", message),
+        };
+        assert!(matches!(
+            bob.receive().await,
+            ServerMessage::Round { number: 1, .. }
+        ));
+        let wrong = (code.language + 1) % code.options.len();
+        bob.send(ClientMessage::Answer {
+            round: 1,
+            answer: wrong,
+        })
+        .await;
+        // only answer once the wrong one counted, so the outcome does not depend on the order
+        loop {
+            match alice.receive().await {
+                ServerMessage::Scoreboard { standings } if standings[1].status == Status::Wrong => {
+                    break
+                }
+                ServerMessage::Scoreboard { .. } => {}
+                message => panic!("expected the scoreboard, got {:?}", message),
+            }
+        }
+        alice
+            .send(ClientMessage::Answer {
+                round: 1,
+                answer: code.language,
+            })
+            .await;
+
+        for player in [&mut alice, &mut bob] {
+            match player.until_over().await {
+                ServerMessage::RoundOver { standings } => {
+                    assert!(matches!(standings[0].status, Status::Correct { .. }));
+                    assert!(standings[0].points > 0);
+                    assert_eq!(standings[1].status, Status::Wrong);
+                    assert_eq!(standings[1].points, 0);
+                    assert_eq!(standings[1].lives, standings[0].lives - 1);
+                }
+                message => panic!("expected the end of the round, got {:?}", message),
+            }
+        }
+
+        alice.send(ClientMessage::Quit).await;
+        bob.send(ClientMessage::Quit).await;
+        host.await.unwrap().unwrap();
+    }
+}
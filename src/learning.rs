@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use anyhow::Result;
+use crossterm::event::{
+    self, Event, KeyCode as Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use language_guesser::engine::Round;
+use serde::Deserialize;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Constraint, Direction, Layout};
+use tui::style::{Color, Style};
+use tui::widgets::{Block, Borders, Paragraph, Wrap};
+use tui::Terminal;
+
+use crate::ui::{contains, draw_buttons};
+
+/// The hints shipped with the game
+const BUNDLED_HINTS: &str = include_str!("hints.yml");
+/// Additional hints of the player, in the config directory
+const HINTS_FILE: &str = "hints.yml";
+/// How many clues are listed per language
+const MAX_CLUES: usize = 3;
+
+/// Something in the code that is typical for a language
+#[derive(Deserialize, Clone, Debug)]
+pub struct Clue {
+    /// Looked for in the code
+    pub token: String,
+    pub note: String,
+}
+
+impl Clue {
+    fn found_in(&self, code: &str) -> bool {
+        code.contains(&self.token)
+    }
+
+    fn token(&self) -> String {
+        format!("`{}`", self.token.trim())
+    }
+}
+
+/// The clues for every language, keyed by the linguist name
+pub struct Hints(BTreeMap<String, Vec<Clue>>);
+
+/// Joins the items like `a, b and c`
+fn enumerate(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+impl Hints {
+    /// Loads the bundled hints, extended by the ones in the config directory
+    pub fn load() -> Result<Self> {
+        let mut hints: BTreeMap<String, Vec<Clue>> = serde_yaml::from_str(BUNDLED_HINTS)?;
+        let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+        if let Some(path) = dirs.find_config_file(HINTS_FILE) {
+            let own: BTreeMap<String, Vec<Clue>> = serde_yaml::from_slice(&fs::read(path)?)?;
+            for (language, clues) in own {
+                hints.entry(language).or_default().extend(clues);
+            }
+        }
+        Ok(Hints(hints))
+    }
+
+    /// Explains what in `code` points to `language` rather than `answer` (`None` if the time ran
+    /// out), one line per sentence
+    pub fn explain(&self, code: &str, language: &str, answer: Option<&str>) -> Vec<String> {
+        let clues = self.0.get(language).map(Vec::as_slice).unwrap_or_default();
+        let found: Vec<&Clue> = clues
+            .iter()
+            .filter(|clue| clue.found_in(code))
+            .take(MAX_CLUES)
+            .collect();
+        let mut lines = Vec::new();
+
+        if found.is_empty() {
+            if clues.is_empty() {
+                lines.push(format!(
+                    "There are no hints for {} yet, you can add some to {} in your config \
+                    directory.",
+                    language, HINTS_FILE
+                ));
+            } else {
+                let tokens: Vec<_> = clues.iter().take(MAX_CLUES).map(Clue::token).collect();
+                lines.push(format!(
+                    "Nothing typical for {} was in this code, next time look out for {}.",
+                    language,
+                    enumerate(&tokens)
+                ));
+            }
+        } else {
+            let tokens: Vec<_> = found.iter().map(|clue| clue.token()).collect();
+            lines.push(match answer {
+                Some(answer) => format!("{} → {}, not {}", enumerate(&tokens), language, answer),
+                None => format!("{} → {}", enumerate(&tokens), language),
+            });
+            for clue in &found {
+                lines.push(format!("  {}: {}", clue.token(), clue.note));
+            }
+        }
+
+        if let Some((answer, clues)) = answer.and_then(|answer| self.0.get_key_value(answer)) {
+            let missing: Vec<_> = clues
+                .iter()
+                .filter(|clue| !clue.found_in(code))
+                .take(MAX_CLUES)
+                .map(|clue| format!("{} ({})", clue.token(), clue.note))
+                .collect();
+            if !missing.is_empty() {
+                lines.push(String::new());
+                lines.push(format!("{} would have {}.", answer, enumerate(&missing)));
+            }
+        }
+        lines
+    }
+}
+
+/// Shows the full code of a missed round together with the `explanation`.
+///
+/// Returns whether the player wants to continue playing.
+pub fn show<B: Backend>(
+    terminal: &mut Terminal<B>,
+    round: &Round,
+    explanation: &[String],
+) -> Result<bool> {
+    loop {
+        let mut buttons = Vec::new();
+        terminal.draw(|f| {
+            let vertical = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(0),
+                    Constraint::Length(explanation.len() as u16 + 2),
+                    Constraint::Length(1),
+                ])
+                .split(f.size());
+            let title = match round.answered_language() {
+                Some(answer) => format!("{} (you answered {})", round.language(), answer),
+                None => format!("{} (timeout)", round.language()),
+            };
+            let code = Paragraph::new(round.code.code.as_str())
+                .wrap(Wrap { trim: false })
+                .block(Block::default().title(title).borders(Borders::ALL));
+            f.render_widget(code, vertical[0]);
+
+            let explanation = Paragraph::new(explanation.join("\n"))
+                .wrap(Wrap { trim: false })
+                .style(Style::default().fg(Color::Yellow))
+                .block(Block::default().title("How to tell").borders(Borders::ALL));
+            f.render_widget(explanation, vertical[1]);
+
+            buttons = draw_buttons(
+                f,
+                vertical[2],
+                &["Continue (Enter or Space)", "Give up (Ctrl+C)"],
+                Alignment::Left,
+            );
+        })?;
+
+        match event::read()? {
+            Event::Key(KeyEvent {
+                code, modifiers, ..
+            }) => match (code, modifiers) {
+                (Key::Char('c'), KeyModifiers::CONTROL) => return Ok(false),
+                (Key::Enter, _) | (Key::Char(' '), _) | (Key::Esc, _) => return Ok(true),
+                _ => {}
+            },
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => match buttons
+                .iter()
+                .position(|&button| contains(button, column, row))
+            {
+                Some(0) => return Ok(true),
+                Some(_) => return Ok(false),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,82 @@
+#![feature(try_blocks)]
+//! The rules of the language guesser without a terminal attached.
+//!
+//! A frontend gets [`Code`] from a [`CodeProvider`] and feeds it together with the input of the
+//! player into an [`engine::Game`], drawing whatever state the game is in afterwards.
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::ArgEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::sampler::Sampler;
+
+pub mod clock;
+pub mod engine;
+pub mod history;
+pub mod lifelines;
+pub mod linguist;
+pub mod net;
+pub mod party;
+pub mod providers;
+pub mod quiz;
+pub mod replay;
+pub mod reveal;
+pub mod sampler;
+pub mod settings;
+pub mod stats;
+pub mod util;
+
+/// The most options a round can have
+pub const MAX_OPTIONS: usize = 31;
+
+/// A piece of code to guess the language of
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Code {
+    /// Where the code comes from, e.g. the URL of the file
+    pub reference: String,
+    pub code: String,
+    /// The index into `options` of the language the code is in
+    pub language: usize,
+    /// The languages the player can choose from
+    pub options: Vec<String>,
+    /// The grapheme the reveal starts at, chosen by the game if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<usize>,
+}
+
+#[async_trait]
+pub trait CodeProvider: Send + Sync {
+    async fn get_code(&self) -> Result<Code>;
+
+    fn retries(&mut self, count: u8);
+    fn options(&mut self, count: u8);
+    /// Replaces how the languages of a round are chosen
+    fn sampler(&mut self, sampler: Arc<dyn Sampler>);
+    /// Makes the choices of the provider reproducible, the same seed chooses the same way
+    fn seed(&mut self, seed: u64);
+}
+
+#[derive(ArgEnum, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum CodeProviders {
+    #[clap(name = "github")]
+    GitHub,
+    /// The snippets of a pack file, or the ones bundled with the game
+    Pack,
+    #[clap(hidden(true))]
+    Test,
+}
+
+impl CodeProviders {
+    /// The providers that can be chosen in the menu
+    pub const ALL: &'static [CodeProviders] = &[CodeProviders::GitHub, CodeProviders::Pack];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CodeProviders::GitHub => "GitHub",
+            CodeProviders::Pack => "Pack",
+            CodeProviders::Test => "Test",
+        }
+    }
+}
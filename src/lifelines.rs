@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Help that can be used a limited number of times per game
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Lifeline {
+    /// Removes half of the wrong options
+    FiftyFifty,
+    /// Reveals more code right away, costing a point like waiting would
+    Reveal,
+    /// Ends the round without losing a life, but also without points
+    Skip,
+}
+
+impl Lifeline {
+    /// In the order of their function keys
+    pub const ALL: &'static [Lifeline] = &[Lifeline::FiftyFifty, Lifeline::Reveal, Lifeline::Skip];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lifeline::FiftyFifty => "50/50",
+            Lifeline::Reveal => "Reveal",
+            Lifeline::Skip => "Skip",
+        }
+    }
+
+    /// The lifeline used with the function key `F<n>`
+    pub fn from_key(n: u8) -> Option<Self> {
+        Self::ALL.get((n as usize).checked_sub(1)?).copied()
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|&lifeline| lifeline == self)
+            .expect("all lifelines are in ALL")
+    }
+}
+
+/// The lifelines left in a game
+pub struct Lifelines {
+    remaining: [u8; 3],
+}
+
+impl Default for Lifelines {
+    /// Every lifeline can be used once per game
+    fn default() -> Self {
+        Self { remaining: [1; 3] }
+    }
+}
+
+impl Lifelines {
+    pub fn remaining(&self, lifeline: Lifeline) -> u8 {
+        self.remaining[lifeline.index()]
+    }
+
+    /// Uses up `lifeline`, returns whether there was one left
+    pub fn take(&mut self, lifeline: Lifeline) -> bool {
+        let remaining = &mut self.remaining[lifeline.index()];
+        if *remaining > 0 {
+            *remaining -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
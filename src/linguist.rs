@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+/// GitHub's list of all the languages it detects
+pub const LANGUAGES_URL: &str =
+    "https://raw.githubusercontent.com/github/linguist/master/lib/linguist/languages.yml";
+
+/// A language as described by linguist
+pub struct Language {
+    pub name: String,
+    /// One of `programming`, `markup`, `data` or `prose`
+    pub kind: String,
+    /// The language this one is counted as on GitHub, e.g. `JavaScript` for `JSX`
+    pub group: Option<String>,
+    /// The color GitHub uses for this language as `#rrggbb`
+    pub color: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    #[serde(rename = "type")]
+    kind: String,
+    group: Option<String>,
+    color: Option<String>,
+}
+
+/// Fetches all languages known to linguist, sorted by name
+pub async fn languages() -> Result<Vec<Language>> {
+    let res = octocrab::instance()
+        ._get(LANGUAGES_URL, None::<&()>)
+        .await?
+        .bytes()
+        .await?;
+
+    let entries: BTreeMap<String, Entry> = serde_yaml::from_slice(&res)?;
+    let mut languages: Vec<_> = entries
+        .into_iter()
+        .map(|(name, Entry { kind, group, color })| Language {
+            name,
+            kind,
+            group,
+            color,
+        })
+        .collect();
+    languages.sort_by_key(|language| language.name.to_lowercase());
+    Ok(languages)
+}
@@ -1,67 +1,60 @@
-#![feature(try_blocks, duration_constants)]
 use anyhow::{bail, Result};
-use async_trait::async_trait;
-use clap::ArgEnum;
-use clap::Clap;
+use chrono::Local;
+use clap::{Clap, Subcommand};
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
-use crossterm::{
-    event::{self, Event, KeyCode as Key},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use rand::prelude::*;
+use crossterm::event::{Event, EventStream, KeyCode as Key};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use futures_util::StreamExt;
+use language_guesser::engine::{Event as GameEvent, Game};
+use language_guesser::history;
+use language_guesser::lifelines::Lifeline;
+use language_guesser::net;
+use language_guesser::party;
+use language_guesser::providers::github::GitHub;
+use language_guesser::providers::pack::{Pack, PackProvider};
+use language_guesser::providers::TestProvider;
+use language_guesser::replay::Replay;
+use language_guesser::sampler::{Adaptive, Uniform};
+use language_guesser::settings::{Difficulty, Mode, ScoringModel, Settings};
+use language_guesser::stats::Stats;
+use language_guesser::{CodeProvider, CodeProviders};
+use std::env;
+use std::fs::File;
+use std::io;
 use std::num::NonZeroU8;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use std::{env, io};
-use tui::backend::CrosstermBackend;
-use tui::layout::{Constraint, Direction, Layout};
-use tui::widgets::{Block, Borders, Paragraph, Row, Table, Wrap};
+use std::time::Duration;
+use tokio::time::{self, MissedTickBehavior};
+use tui::backend::Backend;
+use tui::layout::Rect;
 use tui::Terminal;
 
-use crate::providers::github::GitHub;
-use crate::providers::TestProvider;
+use crate::export::{Format, Report};
+use crate::highscores::{Board, HighScore, HighScores};
+use crate::learning::Hints;
+use crate::ui::{contains, draw_game, setup_terminal, OptionsState, HOTKEYS};
 
-mod providers;
-mod util;
-
-#[derive(Debug)]
-struct Code {
-    reference: String,
-    code: String,
-    language: usize,
-    options: Vec<String>,
-}
-
-#[async_trait]
-trait CodeProvider: Send + Sync {
-    async fn get_code(&self) -> Result<Code>;
-
-    fn retries(&mut self, count: u8);
-    fn options(&mut self, count: u8);
-}
-
-const MAX_POINTS: i32 = 12;
-const STEP_DURATION: Duration = Duration::from_secs(2);
-fn shown_chars(points: i32) -> i32 {
-    2i32.pow((MAX_POINTS - points).max(0) as u32)
-}
-
-#[derive(ArgEnum)]
-enum CodeProviders {
-    #[clap(name = "github")]
-    GitHub,
-    #[clap(hidden(true))]
-    Test,
-}
+mod export;
+mod highscores;
+mod hotseat;
+mod lan;
+mod learning;
+mod menu;
+mod picker;
+mod playback;
+mod presenter;
+mod results;
+mod statistics;
+mod ui;
 
 #[derive(Clap)]
 struct Options {
     /// How many options should be displayed when guessing the language
-    /// This should be at least 2 and at most 10
-    #[clap(long, short, default_value = "4")]
-    options: NonZeroU8,
+    /// This should be at least 2 and at most 31
+    #[clap(long, short)]
+    options: Option<NonZeroU8>,
     /// How often should webrequests be repeted on failure, only relevant for GitHub code
     /// provider
     #[clap(long, short, default_value = "8")]
@@ -70,225 +63,565 @@ struct Options {
     ///
     /// * GitHub: pulls Code from a random repository licensed under MIT
     ///
-    /// * BuiltIn: will use the code provided in ``
-    #[clap(
-        long,
-        short,
-        default_value = "GitHub",
-        arg_enum,
-        case_insensitive(true)
-    )]
-    provider: CodeProviders,
+    /// * Pack: the snippets of the file given with `--pack`, or the ones bundled with the game
+    #[clap(long, short, arg_enum, case_insensitive(true))]
+    provider: Option<CodeProviders>,
+    /// The rules to play with
+    ///
+    /// * classic: you have 5 lives
+    ///
+    /// * sudden-death: you have a single life
+    ///
+    /// * learning: you have 5 lives and get an explanation after every miss
+    ///
+    /// * daily: 10 rounds with 3 lives, the same for everyone on the same day
+    #[clap(long, short, arg_enum, case_insensitive(true))]
+    mode: Option<Mode>,
+    /// How fast the points decrease and more code is revealed
+    #[clap(long, short, arg_enum, case_insensitive(true))]
+    difficulty: Option<Difficulty>,
+    /// How the points of a round decrease
+    ///
+    /// * steps: by one point every step of the reveal
+    ///
+    /// * precise: continuously with the reaction time, worth 100 times the points
+    #[clap(long, arg_enum, case_insensitive(true))]
+    scoring: Option<ScoringModel>,
     /// An optional list of language to use. If omitted, all languages on github will be used.
     #[clap(long, short)]
     languages: Vec<String>,
+    /// Start the game right away instead of showing the menu first
+    #[clap(long)]
+    no_menu: bool,
+    /// Ask more often for the languages you get wrong, offering the ones you confuse them with
+    #[clap(long)]
+    adaptive: bool,
+    /// Makes the game reproducible, the same seed with the same settings chooses the same code and
+    /// options (as long as the provider returns the same results)
+    #[clap(long)]
+    seed: Option<u64>,
+    /// A YAML or JSON file with the snippets to play, implies the pack provider. The daily
+    /// challenge always uses the bundled snippets
+    #[clap(long, parse(from_os_str))]
+    pack: Option<PathBuf>,
+    /// How the results of every game of the session are printed at the end, a table unless all of
+    /// them were daily challenges
+    ///
+    /// * table: a table for reading them in the terminal
+    ///
+    /// * json, csv, markdown: the rounds, settings and time of the game for further processing, an
+    ///   array of games in JSON when more than one was played
+    ///
+    /// * share: a square for every round and the points, to compare with others
+    #[clap(long, arg_enum, case_insensitive(true))]
+    output: Option<Format>,
+    /// Write the results to this file instead of printing them
+    #[clap(long, parse(from_os_str))]
+    output_file: Option<PathBuf>,
+    /// How many players share the screen, from 1 to 4, each answering with their own keys
+    #[clap(long)]
+    players: Option<u8>,
+    /// How many times per second the screen is redrawn at most during a round, it is only redrawn
+    /// when something changed
+    #[clap(long, default_value = "30")]
+    fps: NonZeroU8,
+    #[clap(subcommand)]
+    command: Option<Command>,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let options = Options::parse();
+#[derive(Subcommand)]
+enum Command {
+    /// Print the accuracy and reaction time for every language played so far and which languages
+    /// get mistaken for each other
+    Stats,
+    /// Play a recorded game again
+    Replay {
+        /// The replay file, by default the last game played
+        #[clap(parse(from_os_str))]
+        file: Option<PathBuf>,
+        /// How many times as fast as the original game it is played
+        #[clap(long, default_value = "1")]
+        speed: f64,
+    },
+    /// Host a game for players on other machines, it starts once all of them joined
+    Host {
+        /// How many players take part
+        #[clap(long, default_value = "2")]
+        players: usize,
+        /// The port to listen on, 7878 by default
+        #[clap(long)]
+        port: Option<u16>,
+    },
+    /// Join a game hosted on another machine
+    Join {
+        /// The address of the host, with the port unless it is 7878
+        address: String,
+        /// The name shown to the other players, the user name by default
+        #[clap(long)]
+        name: Option<String>,
+    },
+    /// Present a quiz on a projector, the participants join from their own machines and every
+    /// round starts when the presenter is ready
+    Quiz {
+        /// The port to listen on, 7878 by default
+        #[clap(long)]
+        port: Option<u16>,
+    },
+}
 
-    if !options.languages.is_empty() && options.languages.len() < options.options.get() as usize {
-        bail!("Not enough languages! Need at least {}", options.options);
+/// Plays a game until the player runs out of lives or gives up, reproducibly if there is a `seed`.
+///
+/// The screen is redrawn at most `fps` times per second and only if something on it changed.
+async fn play<B: Backend>(
+    terminal: &mut Terminal<B>,
+    settings: &Settings,
+    seed: Option<u64>,
+    code_provider: Arc<Box<dyn CodeProvider>>,
+    fps: NonZeroU8,
+) -> Result<Game> {
+    let frame = Duration::from_secs(1) / fps.get().into();
+    let mut game = Game::new(settings.clone());
+    if let Some(seed) = seed {
+        game = game.seed(seed);
     }
+    let hints = match settings.mode {
+        Mode::Learning => Some(Hints::load()?),
+        _ => None,
+    };
+    let c = code_provider.clone();
+    let mut next = Box::pin(tokio::spawn(async move { c.get_code().await }));
+    while !game.is_over() {
+        let code = next.await??;
+        let c = code_provider.clone();
+        next = Box::pin(tokio::spawn(async move { c.get_code().await }));
+        let mut options_state = OptionsState::default();
+        let mut code_scroll: u16 = 0;
+        let mut options_area = Rect::default();
+        let mut code_area = Rect::default();
+        let mut resume_area = Rect::default();
+        game.start_round(code);
+        // the learning screen reads the input on its own, so the stream only lives for the round
+        let mut events = EventStream::new();
+        let mut frames = time::interval(frame);
+        frames.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        // the round points shown on the screen, they change whenever the code does
+        let mut drawn_points = None;
+        let mut changed = true;
+        loop {
+            let event = tokio::select! {
+                _ = frames.tick() => {
+                    if game.handle(GameEvent::Tick) {
+                        break;
+                    }
+                    let points = Some(game.round_points());
+                    if changed || points != drawn_points {
+                        terminal.draw(|f| {
+                            let (options, code, resume) = draw_game(
+                                f,
+                                &game,
+                                "Press CTRL+C if you want to give up, Space or Esc to pause, F1-F3 for lifelines.\n\
+                                Press the key next to a language or select it with ↑/↓ (k/j) and Enter.",
+                                &mut options_state,
+                                &mut code_scroll,
+                            );
+                            options_area = options;
+                            code_area = code;
+                            resume_area = resume;
+                        })?;
+                        drawn_points = points;
+                        changed = false;
+                    }
+                    continue;
+                }
+                event = events.next() => match event {
+                    Some(event) => event?,
+                    None => bail!("The terminal stopped sending input!"),
+                },
+            };
+            // moving the mouse changes nothing on the screen, everything else might
+            if let Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Moved,
+                ..
+            }) = event
+            {
+                continue;
+            }
+            changed = true;
+
+            let current = game.current().expect("the round is not over");
+            let options = &current.code().options;
+            let event = match event {
+                Event::Key(KeyEvent {
+                    code: key,
+                    modifiers,
+                    ..
+                }) => {
+                    if let (Key::Char('c'), KeyModifiers::CONTROL) = (key, modifiers) {
+                        Some(GameEvent::Quit)
+                    } else {
+                        match key {
+                            Key::Esc | Key::Char(' ') => Some(current.toggle_pause()),
+                            Key::Up | Key::Char('k') => {
+                                options_state.select_previous(options.len(), current.removed());
+                                None
+                            }
+                            Key::Down | Key::Char('j') => {
+                                options_state.select_next(options.len(), current.removed());
+                                None
+                            }
+                            Key::F(n) => Lifeline::from_key(n).map(GameEvent::Lifeline),
+                            Key::Enter => options_state.selected().map(GameEvent::Answer),
+                            Key::Char(char) => HOTKEYS
+                                .iter()
+                                .position(|&hotkey| hotkey == char)
+                                .map(GameEvent::Answer),
+                            _ => None,
+                        }
+                    }
+                }
+                // nobody is watching the code while another window is in front
+                Event::FocusLost => Some(GameEvent::Pause),
+                Event::Mouse(MouseEvent {
+                    kind, column, row, ..
+                }) => match kind {
+                    MouseEventKind::Down(MouseButton::Left)
+                        if contains(resume_area, column, row) =>
+                    {
+                        Some(GameEvent::Resume)
+                    }
+                    MouseEventKind::Down(MouseButton::Left) => options_state
+                        .option_at(options_area, options.len(), column, row)
+                        .map(GameEvent::Answer),
+                    MouseEventKind::ScrollUp if contains(code_area, column, row) => {
+                        code_scroll = code_scroll.saturating_sub(1);
+                        None
+                    }
+                    MouseEventKind::ScrollDown if contains(code_area, column, row) => {
+                        code_scroll += 1;
+                        None
+                    }
+                    MouseEventKind::ScrollUp if contains(options_area, column, row) => {
+                        options_state.select_previous(options.len(), current.removed());
+                        None
+                    }
+                    MouseEventKind::ScrollDown if contains(options_area, column, row) => {
+                        options_state.select_next(options.len(), current.removed());
+                        None
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            if let Some(event) = event {
+                if game.handle(event) {
+                    break;
+                }
+            }
+        }
+        drop(events);
+
+        let round = game.rounds().last().expect("the round ended");
+        if let Some(hints) = &hints {
+            if round.is_missed() {
+                let explanation = hints.explain(
+                    &round.code.code,
+                    round.language(),
+                    round.answered_language(),
+                );
+                if !learning::show(terminal, round, &explanation)? {
+                    game.handle(GameEvent::Quit);
+                }
+            }
+        }
+    }
+    Ok(game)
+}
 
-    let mut code_provider: Box<dyn CodeProvider> = match options.provider {
+/// The provider of the code for a game with `settings`, choosing reproducibly if there is a `seed`
+async fn code_provider(
+    settings: &Settings,
+    seed: Option<u64>,
+    pack: Option<&Path>,
+    retries: NonZeroU8,
+) -> Result<Arc<Box<dyn CodeProvider>>> {
+    let mut code_provider: Box<dyn CodeProvider> = match settings.provider {
         CodeProviders::GitHub => Box::new(
-            GitHub::new(options.languages)
+            GitHub::new(settings.languages.clone())
                 .await?
                 .token(env::var("LANGUAGE_GUESSER_TOKEN").ok())?,
         ),
+        CodeProviders::Pack => {
+            let pack = Pack::load(pack)?;
+            // snippets can bring their own options, every player needs a key for each of them
+            if settings.players > 1 {
+                let too_many = pack
+                    .snippets
+                    .iter()
+                    .find(|snippet| snippet.options.len() > party::MAX_PARTY_OPTIONS);
+                if let Some(snippet) = too_many {
+                    bail!(
+                        "{} has {} options, too many for more than one player! Can be at most {}",
+                        snippet.reference,
+                        snippet.options.len(),
+                        party::MAX_PARTY_OPTIONS
+                    );
+                }
+            }
+            Box::new(PackProvider::new(pack))
+        }
         CodeProviders::Test => Box::new(TestProvider::default()),
     };
-    code_provider.retries(options.retries.into());
-    code_provider.options(options.options.into());
-    let code_provider = Arc::new(code_provider);
+    code_provider.retries(retries.into());
+    code_provider.options(settings.options);
+    code_provider.sampler(if settings.adaptive {
+        Arc::new(Adaptive::new(&Stats::new(&history::load()?)))
+    } else {
+        Arc::new(Uniform)
+    });
+    if let Some(seed) = seed {
+        code_provider.seed(seed);
+    }
+    Ok(Arc::new(code_provider))
+}
 
-    // Create Terminal
-    let (points, codes) = {
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
-        terminal.clear()?;
+/// The settings and seed a game chosen with `settings` is played with, and the provider of its
+/// code
+async fn prepare(
+    settings: Settings,
+    seed: Option<u64>,
+    pack: Option<&Path>,
+    retries: NonZeroU8,
+) -> Result<(Settings, Option<u64>, Arc<Box<dyn CodeProvider>>)> {
+    // the daily challenge ignores the chosen settings and pack, so everyone plays the same game
+    let (settings, seed, pack) = match settings.mode {
+        Mode::Daily => (
+            settings.daily(),
+            Some(Settings::daily_seed(Local::today())),
+            None,
+        ),
+        _ => (settings, seed, pack),
+    };
+    let code_provider = code_provider(&settings, seed, pack, retries).await?;
+    Ok((settings, seed, code_provider))
+}
 
-        let rng = &mut rand::thread_rng();
+/// Plays the replay in `file` (or of the last game) again, followed by the results
+fn replay(file: Option<PathBuf>, speed: f64) -> Result<()> {
+    if speed <= 0. {
+        bail!("The speed needs to be positive!");
+    }
+    let replay = match file {
+        Some(file) => Replay::load(&file)?,
+        None => match Replay::latest()? {
+            Some(replay) => replay,
+            None => bail!("No game was recorded yet!"),
+        },
+    };
+    let high_scores = HighScores::load()?;
+    let mut terminal = setup_terminal()?;
+    let game = playback::show(&mut terminal, &replay, speed)?;
+    let report = Report {
+        settings: replay.settings,
+        started_at: replay.started_at,
+        finished_at: replay.finished_at,
+        points: game.points(),
+        rounds: game.into_rounds(),
+        seed: None,
+    };
+    results::show(&mut terminal, &report, &high_scores, false)?;
+    Ok(())
+}
 
-        let mut points_total = 0;
-        let mut codes: Vec<(Code, Option<i32>)> = Vec::new();
-        let mut lives = 5;
-        let c = code_provider.clone();
-        let mut next = Box::pin(tokio::spawn(async move { c.get_code().await }));
-        'main: loop {
-            if lives == 0 {
-                break 'main;
-            }
-            let code = next.await??;
-            let c = code_provider.clone();
-            next = Box::pin(tokio::spawn(async move { c.get_code().await }));
-            let language_descriptions = code
-                .options
-                .clone()
-                .into_iter()
-                .zip(1..)
-                .collect::<Vec<_>>();
-            let mut points_round = MAX_POINTS;
-            let origin = loop {
-                let origin = rng.gen_range(0..code.code.len());
-                if !code.code.chars().nth(origin).unwrap().is_whitespace() {
-                    break origin as i32;
-                };
-            };
-            let text = code.code.clone();
-            let mut last = Instant::now();
-            'tick: loop {
-                if Instant::now().duration_since(last) > STEP_DURATION {
-                    if points_round == 0 {
-                        lives -= 1;
-                        if lives == 0 {
-                            break 'main;
-                        } else {
-                            break 'tick;
-                        }
-                    } else {
-                        points_round -= 1;
-                    }
-                    last = Instant::now();
-                }
-                terminal.draw(|f| {
-                    let vertical = Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([Constraint::Length(6), Constraint::Percentage(80)].as_ref())
-                        .split(f.size());
-                    let block = Block::default().borders(Borders::ALL);
-                    {
-                        let inner = Layout::default()
-                            .direction(Direction::Vertical)
-                            .constraints(
-                                [
-                                    Constraint::Length(2),
-                                    Constraint::Length(1),
-                                    Constraint::Percentage(100),
-                                ]
-                                .as_ref(),
-                            )
-                            .split(block.inner(vertical[0]));
-                        let paragraph = Paragraph::new(
-                            "Press CTRL+C if you want to give up.\nPress 1-4 to guess a language.",
-                        );
-                        f.render_widget(paragraph, inner[0]);
-                        let bottom = Layout::default()
-                            .direction(Direction::Horizontal)
-                            .constraints(
-                                [
-                                    Constraint::Ratio(1, 3),
-                                    Constraint::Ratio(1, 3),
-                                    Constraint::Ratio(1, 3),
-                                ]
-                                .as_ref(),
-                            )
-                            .split(inner[2]);
-                        let p = Paragraph::new(format!("Total Points: {}", points_total));
-                        f.render_widget(p, bottom[0]);
+#[tokio::main]
+async fn main() -> Result<()> {
+    let options = Options::parse();
 
-                        let p = Paragraph::new(format!("Round Points: {}", points_round));
-                        f.render_widget(p, bottom[1]);
+    match options.command {
+        Some(Command::Stats) => {
+            statistics::print(&Stats::new(&history::load()?));
+            return Ok(());
+        }
+        Some(Command::Replay { file, speed }) => return replay(file, speed),
+        Some(Command::Join { address, name }) => {
+            let name = name
+                .or_else(|| env::var("USER").ok())
+                .unwrap_or_else(|| String::from("Player"));
+            let mut terminal = setup_terminal()?;
+            let standings = lan::join(&mut terminal, &address, name, options.fps).await;
+            drop(terminal);
+            lan::print_standings(&standings?);
+            return Ok(());
+        }
+        Some(Command::Host { .. }) | Some(Command::Quiz { .. }) | None => {}
+    }
 
-                        let p = Paragraph::new(format!("Lives: {}", "🫀".repeat(lives)));
-                        f.render_widget(p, bottom[2]);
-                    }
-                    f.render_widget(block, vertical[0]);
+    // settings not given on the command line default to the ones chosen last time
+    let mut settings = Settings::load()?;
+    if let Some(count) = options.options {
+        settings.options = count.get();
+    }
+    if let Some(provider) = options.provider {
+        settings.provider = provider;
+    }
+    if options.pack.is_some() {
+        settings.provider = CodeProviders::Pack;
+    }
+    if let Some(players) = options.players {
+        settings.players = players;
+    }
+    if let Some(mode) = options.mode {
+        settings.mode = mode;
+    }
+    if let Some(difficulty) = options.difficulty {
+        settings.difficulty = difficulty;
+    }
+    if let Some(model) = options.scoring {
+        settings.scoring.model = model;
+    }
+    if !options.languages.is_empty() {
+        settings.languages = options.languages;
+    }
+    if options.adaptive {
+        settings.adaptive = true;
+    }
+    if options.no_menu {
+        settings.validate()?;
+    }
 
-                    let horizontal = Layout::default()
-                        .direction(Direction::Horizontal)
-                        .constraints([Constraint::Length(20), Constraint::Min(0)])
-                        .split(vertical[1]);
-                    let shown_chars = if points_round == 0 {
-                        text.len() as i32
-                    } else {
-                        shown_chars(points_round)
-                    };
-                    let start = (text.len() as i32 - shown_chars)
-                        .min(origin as i32 - shown_chars / 2)
-                        .max(0);
-                    let code = Paragraph::new(
-                        text.as_str()
-                            .chars()
-                            .skip(start as usize)
-                            .take(shown_chars as usize)
-                            .collect::<String>(),
-                    )
-                    .wrap(Wrap { trim: false })
-                    .block(Block::default().title("Code").borders(Borders::ALL));
+    if let Some(Command::Host { players, port }) = options.command {
+        // the players are on their own machines
+        settings.players = 1;
+        settings.validate()?;
+        let code_provider = code_provider(
+            &settings,
+            options.seed,
+            options.pack.as_deref(),
+            options.retries,
+        )
+        .await?;
+        let port = port.unwrap_or(net::DEFAULT_PORT);
+        return lan::host(settings, options.seed, code_provider, port, players).await;
+    }
+    if let Some(Command::Quiz { port }) = options.command {
+        settings.players = 1;
+        settings.validate()?;
+        let code_provider = code_provider(
+            &settings,
+            options.seed,
+            options.pack.as_deref(),
+            options.retries,
+        )
+        .await?;
+        let port = port.unwrap_or(net::DEFAULT_PORT);
+        let mut terminal = setup_terminal()?;
+        let standings = presenter::present(
+            &mut terminal,
+            settings,
+            options.seed,
+            code_provider,
+            port,
+            options.fps,
+        )
+        .await;
+        drop(terminal);
+        lan::print_standings(&standings?);
+        return Ok(());
+    }
 
-                    f.render_widget(code, horizontal[1]);
-                    let table =
-                        Table::new(language_descriptions.iter().map(|(language, number)| {
-                            Row::new(vec![number.to_string(), language.to_string()])
-                        }))
-                        .widths(&[Constraint::Length(3), Constraint::Percentage(100)])
-                        .block(Block::default().title("Languages").borders(Borders::ALL));
-                    f.render_widget(table, horizontal[0]);
-                })?;
+    let mut high_scores = HighScores::load()?;
+    // without the menu the first game is known already, so it can fail before the terminal is
+    // taken over
+    let mut prepared = if options.no_menu {
+        Some(
+            prepare(
+                settings.clone(),
+                options.seed,
+                options.pack.as_deref(),
+                options.retries,
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+    let mut terminal = setup_terminal()?;
 
-                if event::poll(Duration::ZERO)? {
-                    if let Event::Key(KeyEvent {
-                        code: key,
-                        modifiers,
-                    }) = event::read()?
-                    {
-                        if let (Key::Char('c'), KeyModifiers::CONTROL) = (key, modifiers) {
-                            break 'main;
-                        }
-                        if let Key::Char(char) = key {
-                            if char >= '1' && char <= '9' {
-                                if char as usize - '1' as usize == code.language {
-                                    points_total += points_round;
-                                    codes.push((code, Some(points_round)));
-                                } else {
-                                    lives -= 1;
-                                    codes.push((code, None));
-                                }
-                                break 'tick;
-                            }
-                        }
-                    }
-                }
+    let mut games = Vec::new();
+    loop {
+        let (settings, seed, code_provider) = match prepared.take() {
+            Some(prepared) => prepared,
+            None => {
+                let settings = if options.no_menu {
+                    settings.clone()
+                } else if let Some(chosen) =
+                    menu::show(&mut terminal, settings.clone(), &high_scores).await?
+                {
+                    chosen.save()?;
+                    settings = chosen.clone();
+                    chosen
+                } else {
+                    break;
+                };
+                prepare(
+                    settings,
+                    options.seed,
+                    options.pack.as_deref(),
+                    options.retries,
+                )
+                .await?
             }
+        };
+
+        // games of several players are not recorded, the history and high scores are personal
+        if settings.players > 1 {
+            let party =
+                hotseat::play(&mut terminal, &settings, seed, code_provider, options.fps).await?;
+            if !hotseat::show(&mut terminal, &party)? {
+                break;
+            }
+            continue;
         }
-        execute!(terminal.backend_mut(), LeaveAlternateScreen,)?;
-        disable_raw_mode()?;
-        (points_total, codes)
-    };
 
-    println!("\nYour total points {}!\n\nDetails:", points);
-    {
-        use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
-        use comfy_table::presets::UTF8_FULL;
-        use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_header(
-                vec!["Score", "Language", "Reference"]
-                    .iter()
-                    .map(|s| Cell::new(s).set_alignment(CellAlignment::Center)),
-            );
-        for (code, points) in codes {
-            table.add_row(vec![
-                Cell::new(
-                    points
-                        .map(|x| x.to_string())
-                        .unwrap_or_else(|| String::from("---")),
-                ),
-                Cell::new(code.options[code.language].clone()),
-                Cell::new(code.reference),
-            ]);
+        let started_at = Local::now();
+        let game = play(&mut terminal, &settings, seed, code_provider, options.fps).await?;
+        let finished_at = Local::now();
+        game.replay(started_at, finished_at).save()?;
+        let report = Report {
+            settings,
+            started_at,
+            finished_at,
+            points: game.points(),
+            rounds: game.into_rounds(),
+            seed,
+        };
+        let new_best = high_scores.add(HighScore {
+            board: Board::new(&report.settings),
+            points: report.points,
+            rounds: report.rounds.len(),
+            date: started_at,
+        });
+        high_scores.save()?;
+        history::append(started_at, &report.settings, &report.rounds)?;
+        let new_game = results::show(&mut terminal, &report, &high_scores, new_best)?;
+        games.push(report);
+        if !new_game {
+            break;
         }
-        println!("{}", table);
+    }
+    drop(terminal);
+
+    if games.is_empty() {
+        return Ok(());
+    }
+    let daily = games
+        .iter()
+        .all(|report| report.settings.mode == Mode::Daily);
+    let format = options
+        .output
+        .unwrap_or(if daily { Format::Share } else { Format::Table });
+    match options.output_file {
+        Some(path) => export::write_all(&games, format, &mut File::create(path)?)?,
+        None => export::write_all(&games, format, &mut io::stdout())?,
     }
     Ok(())
 }
@@ -0,0 +1,292 @@
+use anyhow::Result;
+use crossterm::event::{
+    self, Event, KeyCode as Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use language_guesser::linguist::{self, Language};
+use language_guesser::party;
+use language_guesser::settings::{Difficulty, Mode, ScoringModel, Settings};
+use language_guesser::stats::Stats;
+use language_guesser::{history, CodeProviders};
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::widgets::{Block, Borders, Paragraph, Row, Table};
+use tui::Terminal;
+
+use crate::highscores::{Board, HighScores, TOP};
+use crate::ui::{centered, contains, high_scores_table, HOTKEYS};
+use crate::{picker, statistics};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Item {
+    Mode,
+    Difficulty,
+    Provider,
+    Languages,
+    Options,
+    Players,
+    Scoring,
+    Adaptive,
+    Start,
+    Statistics,
+    Quit,
+}
+
+const ITEMS: &[Item] = &[
+    Item::Mode,
+    Item::Difficulty,
+    Item::Provider,
+    Item::Languages,
+    Item::Options,
+    Item::Players,
+    Item::Scoring,
+    Item::Adaptive,
+    Item::Start,
+    Item::Statistics,
+    Item::Quit,
+];
+
+/// Returns the element after (or before) `current` in `all`, wrapping around at the ends
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, forward: bool) -> T {
+    let idx = all.iter().position(|&t| t == current).unwrap_or_default();
+    if forward {
+        all[(idx + 1) % all.len()]
+    } else {
+        all[(idx + all.len() - 1) % all.len()]
+    }
+}
+
+struct Menu {
+    settings: Settings,
+    selected: usize,
+    error: Option<String>,
+}
+
+/// What should happen after an input was handled
+enum Action {
+    None,
+    PickLanguages,
+    Statistics,
+    Start,
+    Quit,
+}
+
+impl Menu {
+    fn label(item: Item) -> &'static str {
+        match item {
+            Item::Mode => "Mode",
+            Item::Difficulty => "Difficulty",
+            Item::Provider => "Provider",
+            Item::Languages => "Languages",
+            Item::Options => "Options",
+            Item::Players => "Players",
+            Item::Scoring => "Scoring",
+            Item::Adaptive => "Adaptive",
+            Item::Start => "Start",
+            Item::Statistics => "Statistics",
+            Item::Quit => "Quit",
+        }
+    }
+
+    fn value(&self, item: Item) -> String {
+        match item {
+            Item::Mode => self.settings.mode.name().to_string(),
+            Item::Difficulty => self.settings.difficulty.name().to_string(),
+            Item::Provider => self.settings.provider.name().to_string(),
+            Item::Languages if self.settings.languages.is_empty() => String::from("All"),
+            Item::Languages => self.settings.languages.join(", "),
+            Item::Options => self.settings.options.to_string(),
+            Item::Players => self.settings.players.to_string(),
+            Item::Scoring => self.settings.scoring.model.name().to_string(),
+            Item::Adaptive if self.settings.adaptive => String::from("On"),
+            Item::Adaptive => String::from("Off"),
+            Item::Start | Item::Statistics | Item::Quit => String::new(),
+        }
+    }
+
+    /// Changes the value of `item` to the next or previous choice
+    fn change(&mut self, item: Item, forward: bool) {
+        let settings = &mut self.settings;
+        match item {
+            Item::Mode => settings.mode = cycle(Mode::ALL, settings.mode, forward),
+            Item::Difficulty => {
+                settings.difficulty = cycle(Difficulty::ALL, settings.difficulty, forward)
+            }
+            Item::Provider => {
+                settings.provider = cycle(CodeProviders::ALL, settings.provider, forward)
+            }
+            Item::Options => {
+                settings.options = if forward {
+                    (settings.options + 1).min(HOTKEYS.len() as u8)
+                } else {
+                    settings.options.saturating_sub(1).max(2)
+                }
+            }
+            Item::Players => {
+                settings.players = if forward {
+                    (settings.players + 1).min(party::MAX_PLAYERS as u8)
+                } else {
+                    settings.players.saturating_sub(1).max(1)
+                }
+            }
+            Item::Scoring => {
+                settings.scoring.model = cycle(ScoringModel::ALL, settings.scoring.model, forward)
+            }
+            Item::Adaptive => settings.adaptive = !settings.adaptive,
+            Item::Languages | Item::Start | Item::Statistics | Item::Quit => {}
+        }
+    }
+
+    /// Activates `item` like pressing enter on it would
+    fn activate(&mut self, item: Item) -> Action {
+        match item {
+            Item::Start => match self.settings.validate() {
+                Ok(()) => return Action::Start,
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            Item::Quit => return Action::Quit,
+            Item::Languages => return Action::PickLanguages,
+            Item::Statistics => return Action::Statistics,
+            item => self.change(item, true),
+        }
+        Action::None
+    }
+
+    fn handle(&mut self, event: Event, table_area: Rect) -> Action {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => return Action::Quit,
+            Event::Key(KeyEvent { code: key, .. }) => {
+                self.error = None;
+                let item = ITEMS[self.selected];
+                match key {
+                    Key::Up | Key::Char('k') => {
+                        self.selected = (self.selected + ITEMS.len() - 1) % ITEMS.len()
+                    }
+                    Key::Down | Key::Char('j') => self.selected = (self.selected + 1) % ITEMS.len(),
+                    Key::Left | Key::Char('h') => self.change(item, false),
+                    Key::Right | Key::Char('l') => self.change(item, true),
+                    Key::Enter => return self.activate(item),
+                    Key::Esc | Key::Char('q') => return Action::Quit,
+                    _ => {}
+                }
+            }
+            Event::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) => match kind {
+                MouseEventKind::Down(MouseButton::Left) if contains(table_area, column, row) => {
+                    self.error = None;
+                    self.selected = (row - table_area.y) as usize;
+                    return self.activate(ITEMS[self.selected]);
+                }
+                MouseEventKind::ScrollUp => self.selected = self.selected.saturating_sub(1),
+                MouseEventKind::ScrollDown => {
+                    self.selected = (self.selected + 1).min(ITEMS.len() - 1)
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+/// Shows the main menu until the player starts a game or quits, together with the high scores
+/// for the chosen settings.
+///
+/// Returns the settings to play with or `None` if the player wants to quit.
+pub async fn show<B: Backend>(
+    terminal: &mut Terminal<B>,
+    settings: Settings,
+    high_scores: &HighScores,
+) -> Result<Option<Settings>> {
+    let mut menu = Menu {
+        settings,
+        selected: ITEMS.len() - 3,
+        error: None,
+    };
+    // only fetched once the language picker is opened
+    let mut languages: Option<Vec<Language>> = None;
+    loop {
+        let mut table_area = Default::default();
+        terminal.draw(|f| {
+            let area = centered(f.size(), 60, ITEMS.len() as u16 + TOP as u16 + 9);
+            let block = Block::default()
+                .title("Language Guesser")
+                .borders(Borders::ALL);
+            let inner = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(ITEMS.len() as u16),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(TOP as u16 + 3),
+                ])
+                .split(block.inner(area));
+            f.render_widget(block, area);
+
+            let table = Table::new(ITEMS.iter().enumerate().map(|(idx, &item)| {
+                let row = Row::new(vec![Menu::label(item).to_string(), menu.value(item)]);
+                if idx == menu.selected {
+                    row.style(Style::default().add_modifier(Modifier::REVERSED))
+                } else {
+                    row
+                }
+            }))
+            .widths(&[Constraint::Length(12), Constraint::Percentage(100)]);
+            f.render_widget(table, inner[0]);
+            table_area = inner[0];
+
+            let help = "↑/↓ select, ←/→ change, Enter confirm, Esc quit";
+            f.render_widget(Paragraph::new(help), inner[2]);
+            if let Some(error) = &menu.error {
+                let error = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
+                f.render_widget(error, inner[3]);
+            }
+
+            let scores = high_scores.top(&Board::new(&menu.settings));
+            f.render_widget(high_scores_table(&scores, None), inner[4]);
+        })?;
+
+        match menu.handle(event::read()?, table_area) {
+            Action::None => {}
+            Action::PickLanguages => {
+                let languages = match &mut languages {
+                    Some(languages) => languages,
+                    None => {
+                        terminal.draw(|f| {
+                            let area = centered(f.size(), 30, 3);
+                            let loading = Paragraph::new("Loading languages...")
+                                .block(Block::default().borders(Borders::ALL));
+                            f.render_widget(loading, area);
+                        })?;
+                        match linguist::languages().await {
+                            Ok(loaded) => languages.insert(loaded),
+                            Err(e) => {
+                                menu.error = Some(format!("Could not load the languages: {}", e));
+                                continue;
+                            }
+                        }
+                    }
+                };
+                let settings = &mut menu.settings;
+                if let Some(selection) = picker::pick(
+                    terminal,
+                    languages,
+                    &settings.languages,
+                    &mut settings.language_sets,
+                    settings.options,
+                )? {
+                    settings.languages = selection;
+                }
+            }
+            Action::Statistics => statistics::show(terminal, &Stats::new(&history::load()?))?,
+            Action::Start => return Ok(Some(menu.settings)),
+            Action::Quit => return Ok(None),
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::engine::{Outcome, Round};
+use crate::party::Party;
+use crate::quiz::Quiz;
+use crate::settings::Settings;
+use crate::Code;
+
+/// The port a game is hosted on unless another one is chosen
+pub const DEFAULT_PORT: u16 = 7878;
+/// The longest message [`receive`] reads, far more than the code of a round needs
+pub const MAX_MESSAGE: u64 = 1 << 20;
+
+/// How the current round is going for a player, or how the last one went
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Status {
+    /// Still in the round without an answer
+    Guessing,
+    Correct {
+        points: i32,
+    },
+    Wrong,
+    Timeout,
+    /// Another player answered correctly first
+    Beaten,
+    /// Lost all lives or left the game
+    Out,
+}
+
+impl Status {
+    /// How `round` ended for the player
+    pub fn of(round: &Round) -> Self {
+        match round.outcome {
+            Outcome::Guess if round.is_correct() => Status::Correct {
+                points: round.points,
+            },
+            Outcome::Guess => Status::Wrong,
+            Outcome::Timeout => Status::Timeout,
+            Outcome::Beaten => Status::Beaten,
+            Outcome::Quit | Outcome::Skip => Status::Out,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    /// Like `✔ 12` or `timeout`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Guessing => write!(f, "guessing..."),
+            Status::Correct { points } => write!(f, "✔ {}", points),
+            Status::Wrong => write!(f, "✘ wrong"),
+            Status::Timeout => write!(f, "✘ timeout"),
+            Status::Beaten => write!(f, "beaten"),
+            Status::Out => write!(f, "out"),
+        }
+    }
+}
+
+/// Where a player stands in the game
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Standing {
+    pub name: String,
+    pub points: i32,
+    pub lives: usize,
+    pub status: Status,
+}
+
+/// The standing of every player of `party`, `names` being their names in the same order
+pub fn standings(party: &Party, names: &[String]) -> Vec<Standing> {
+    party
+        .players()
+        .iter()
+        .zip(names)
+        .enumerate()
+        .map(|(player, (game, name))| {
+            let status = match game.rounds().last() {
+                _ if game.current().is_some() => Status::Guessing,
+                Some(round) if party.in_round(player) => Status::of(round),
+                _ => Status::Out,
+            };
+            Standing {
+                name: name.clone(),
+                points: game.points(),
+                lives: game.lives(),
+                status,
+            }
+        })
+        .collect()
+}
+
+/// The standing of every participant of `quiz`, `names` being their names in the same order.
+///
+/// Nobody loses lives in a quiz, so they are always 0.
+pub fn quiz_standings(quiz: &Quiz, names: &[String]) -> Vec<Standing> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(participant, name)| {
+            let last = quiz
+                .rounds()
+                .last()
+                .and_then(|round| round.answers[participant].as_ref());
+            let status = match quiz.game(participant) {
+                _ if quiz.has_left(participant) => Status::Out,
+                Some(game) if game.current().is_some() => Status::Guessing,
+                Some(game) => Status::of(game.rounds().last().expect("the round ended")),
+                None => last.map_or(Status::Guessing, Status::of),
+            };
+            Standing {
+                name: name.clone(),
+                points: quiz.points()[participant],
+                lives: 0,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// What the host of a game sends to the players
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ServerMessage {
+    /// The player joined as the player with this index
+    Welcome {
+        player: usize,
+        settings: Settings,
+    },
+    /// The round with this number starts, the origin of the code is set so everyone sees the same
+    /// part
+    Round {
+        number: usize,
+        code: Code,
+    },
+    /// An answer changed the standings during the round
+    Scoreboard {
+        standings: Vec<Standing>,
+    },
+    RoundOver {
+        standings: Vec<Standing>,
+    },
+    GameOver {
+        standings: Vec<Standing>,
+    },
+}
+
+/// What a player sends to the host
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ClientMessage {
+    /// The first message of every player
+    Join { name: String },
+    /// The player chose the option with the index `answer` in the round with the number `round`,
+    /// answers arriving after the round ended are ignored that way
+    Answer { round: usize, answer: usize },
+    /// The player gives up
+    Quit,
+}
+
+/// Writes `message` as a single line of JSON
+pub async fn send<W, T>(writer: &mut W, message: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+/// Reads the next message written by [`send`], `None` once the other side closed the connection.
+///
+/// Messages longer than [`MAX_MESSAGE`] are an error, a connection can't make the other side take
+/// up all of its memory.
+pub async fn receive<R, T>(reader: &mut R) -> Result<Option<T>>
+where
+    R: AsyncBufRead + Unpin,
+    T: DeserializeOwned,
+{
+    let mut line = String::new();
+    if (&mut *reader)
+        .take(MAX_MESSAGE)
+        .read_line(&mut line)
+        .await?
+        == 0
+    {
+        return Ok(None);
+    }
+    if line.len() as u64 == MAX_MESSAGE && !line.ends_with('\n') {
+        bail!("The message is longer than {} bytes!", MAX_MESSAGE);
+    }
+    Ok(Some(serde_json::from_str(&line)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn receive_reads_messages_until_closed() {
+        let mut input: &[u8] = b"\"Quit\"\n{\"Join\":{\"name\":\"Alice\"}}\n";
+        assert!(matches!(
+            receive(&mut input).await.unwrap(),
+            Some(ClientMessage::Quit)
+        ));
+        assert!(matches!(
+            receive(&mut input).await.unwrap(),
+            Some(ClientMessage::Join { name }) if name == "Alice"
+        ));
+        assert!(receive::<_, ClientMessage>(&mut input)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn receive_refuses_too_long_messages() {
+        // valid, it would be read without the limit
+        let name = "a".repeat(MAX_MESSAGE as usize);
+        let line = format!("{{\"Join\":{{\"name\":\"{}\"}}}}\n", name);
+        let mut input = line.as_bytes();
+        assert!(receive::<_, ClientMessage>(&mut input).await.is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::clock::Clock;
+use crate::engine::{Current, Event, Game};
+use crate::settings::Settings;
+use crate::Code;
+
+/// The most players that can share a screen
+pub const MAX_PLAYERS: usize = 4;
+/// The most options a round with more than one player can have, every player needs a key for
+/// each of them
+pub const MAX_PARTY_OPTIONS: usize = 4;
+
+/// Several players guessing the same code at the same time, on one screen or over the network.
+///
+/// Every player plays their own [`Game`] with its own lives, points and streak. The first correct
+/// answer scores, the players that did not answer yet leave the round with
+/// [`Outcome::Beaten`](crate::engine::Outcome::Beaten) and keep their lives. A wrong answer ends
+/// the round only for the player that gave it.
+pub struct Party {
+    players: Vec<Game>,
+    /// Which players started the current (or last) round, the others were out already
+    in_round: Vec<bool>,
+}
+
+impl Party {
+    pub fn new(settings: Settings, players: usize) -> Self {
+        Self {
+            players: (0..players).map(|_| Game::new(settings.clone())).collect(),
+            in_round: vec![false; players],
+        }
+    }
+
+    /// Replaces where the time is taken from for every player
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.players = self
+            .players
+            .into_iter()
+            .map(|game| game.clock(clock.clone()))
+            .collect();
+        self
+    }
+
+    /// Makes the random choices of the game reproducible
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.players = self
+            .players
+            .into_iter()
+            .map(|game| game.seed(seed))
+            .collect();
+        self
+    }
+
+    /// The game of every player, in the order of the players
+    pub fn players(&self) -> &[Game] {
+        &self.players
+    }
+
+    /// Whether `player` started the current round, or the last one if none is being played
+    pub fn in_round(&self, player: usize) -> bool {
+        self.in_round[player]
+    }
+
+    /// Whether none of the players can play another round
+    pub fn is_over(&self) -> bool {
+        self.players.iter().all(Game::is_over)
+    }
+
+    /// The round that is being played, as long as one of the players is still in it
+    pub fn current(&self) -> Option<&Current> {
+        self.players.iter().find_map(Game::current)
+    }
+
+    /// The points a correct answer would get right now, without bonus and multiplier
+    pub fn round_points(&self) -> i32 {
+        self.players
+            .iter()
+            .find(|game| game.current().is_some())
+            .map_or(0, Game::round_points)
+    }
+
+    /// Starts a round with `code` for every player that is not out yet, all of them see the same
+    /// part of the code
+    pub fn start_round(&mut self, mut code: Code) {
+        for (game, in_round) in self.players.iter_mut().zip(&mut self.in_round) {
+            *in_round = !game.is_over();
+            if *in_round {
+                game.start_round(code.clone());
+                code.origin = game.current().map(Current::origin);
+            }
+        }
+    }
+
+    /// Applies `event` to the round of every player, answers are given with [`Party::answer`].
+    ///
+    /// Returns whether the round ended for all players.
+    pub fn handle(&mut self, event: Event) -> bool {
+        for game in &mut self.players {
+            game.handle(event);
+        }
+        self.current().is_none()
+    }
+
+    /// `player` chose the option `answer`, ignored if they already answered in this round.
+    ///
+    /// Returns whether the round ended for all players.
+    pub fn answer(&mut self, player: usize, answer: usize) -> bool {
+        let game = &mut self.players[player];
+        if game.current().is_none() || !game.handle(Event::Answer(answer)) {
+            return false;
+        }
+        let correct = game.rounds().last().expect("the round ended").is_correct();
+        if correct {
+            self.handle(Event::Beaten)
+        } else {
+            self.current().is_none()
+        }
+    }
+
+    /// `player` gave up, the others keep playing.
+    ///
+    /// Returns whether the round ended for all players.
+    pub fn leave(&mut self, player: usize) -> bool {
+        self.players[player].handle(Event::Quit);
+        self.current().is_none()
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use crossterm::event::{
+    self, Event, KeyCode as Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use language_guesser::linguist::Language;
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use tui::Terminal;
+
+use crate::ui::{contains, scroll_offset};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Focus {
+    Languages,
+    Sets,
+}
+
+struct Picker<'a> {
+    languages: &'a [Language],
+    /// A copy of the saved sets, they are only changed once the player is done
+    sets: BTreeMap<String, Vec<String>>,
+    options: u8,
+    selection: BTreeSet<String>,
+    search: String,
+    /// Indices into `languages` of the languages matching `search`
+    filtered: Vec<usize>,
+    focus: Focus,
+    cursor: usize,
+    offset: usize,
+    set_cursor: usize,
+    /// The name typed so far while saving the selection as a set
+    naming: Option<String>,
+    error: Option<String>,
+}
+
+/// What should happen after an input was handled
+enum Action {
+    None,
+    Done,
+    Cancel,
+}
+
+/// Parses a `#rrggbb` color as used by linguist
+fn color(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#')?;
+    let channel = |idx: usize| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+impl<'a> Picker<'a> {
+    fn update_filter(&mut self) {
+        let search = self.search.to_lowercase();
+        self.filtered = self
+            .languages
+            .iter()
+            .enumerate()
+            .filter(|(_, language)| language.name.to_lowercase().contains(&search))
+            .map(|(idx, _)| idx)
+            .collect();
+        self.cursor = 0;
+    }
+
+    fn toggle(&mut self, idx: usize) {
+        let name = &self.languages[self.filtered[idx]].name;
+        if !self.selection.remove(name) {
+            self.selection.insert(name.clone());
+        }
+    }
+
+    fn apply_set(&mut self, idx: usize) {
+        if let Some(languages) = self.sets.values().nth(idx) {
+            self.selection = languages.iter().cloned().collect();
+        }
+    }
+
+    fn finish(&mut self) -> Action {
+        if !self.selection.is_empty() && self.selection.len() < self.options as usize {
+            self.error = Some(format!(
+                "Select at least {} languages or none to use all of them",
+                self.options
+            ));
+            Action::None
+        } else {
+            Action::Done
+        }
+    }
+
+    fn move_cursor(&mut self, forward: bool, amount: usize) {
+        let (cursor, len) = match self.focus {
+            Focus::Languages => (&mut self.cursor, self.filtered.len()),
+            Focus::Sets => (&mut self.set_cursor, self.sets.len()),
+        };
+        *cursor = if forward {
+            (*cursor + amount).min(len.saturating_sub(1))
+        } else {
+            cursor.saturating_sub(amount)
+        };
+    }
+
+    fn handle_naming(&mut self, key: Key, modifiers: KeyModifiers) -> Action {
+        let name = self.naming.as_mut().expect("only called while naming");
+        match (key, modifiers) {
+            (Key::Char('c'), KeyModifiers::CONTROL) => return Action::Cancel,
+            (Key::Char(char), KeyModifiers::NONE) | (Key::Char(char), KeyModifiers::SHIFT) => {
+                name.push(char)
+            }
+            (Key::Backspace, _) => {
+                name.pop();
+            }
+            (Key::Enter, _) if !name.trim().is_empty() => {
+                self.sets.insert(
+                    name.trim().to_string(),
+                    self.selection.iter().cloned().collect(),
+                );
+                self.naming = None;
+            }
+            (Key::Esc, _) => self.naming = None,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_key(&mut self, key: Key, modifiers: KeyModifiers) -> Action {
+        match (key, modifiers) {
+            (Key::Char('c'), KeyModifiers::CONTROL) => return Action::Cancel,
+            (Key::Char('s'), KeyModifiers::CONTROL) => self.naming = Some(String::new()),
+            (Key::Esc, _) => return self.finish(),
+            (Key::Tab, _) => {
+                self.focus = match self.focus {
+                    Focus::Languages => Focus::Sets,
+                    Focus::Sets => Focus::Languages,
+                }
+            }
+            (Key::Up, _) => self.move_cursor(false, 1),
+            (Key::Down, _) => self.move_cursor(true, 1),
+            (Key::PageUp, _) => self.move_cursor(false, 10),
+            (Key::PageDown, _) => self.move_cursor(true, 10),
+            (Key::Enter, _) => match self.focus {
+                Focus::Languages if !self.filtered.is_empty() => self.toggle(self.cursor),
+                Focus::Sets => self.apply_set(self.set_cursor),
+                _ => {}
+            },
+            (Key::Delete, _) if self.focus == Focus::Sets => {
+                if let Some(name) = self.sets.keys().nth(self.set_cursor).cloned() {
+                    self.sets.remove(&name);
+                    self.set_cursor = self.set_cursor.min(self.sets.len().saturating_sub(1));
+                }
+            }
+            (Key::Char(char), KeyModifiers::NONE) | (Key::Char(char), KeyModifiers::SHIFT) => {
+                self.search.push(char);
+                self.update_filter();
+            }
+            (Key::Backspace, _) => {
+                self.search.pop();
+                self.update_filter();
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle(&mut self, event: Event, languages_area: Rect, sets_area: Rect) -> Action {
+        match event {
+            Event::Key(KeyEvent {
+                code: key,
+                modifiers,
+                ..
+            }) if self.naming.is_some() => return self.handle_naming(key, modifiers),
+            Event::Key(KeyEvent {
+                code: key,
+                modifiers,
+                ..
+            }) => {
+                self.error = None;
+                return self.handle_key(key, modifiers);
+            }
+            Event::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) => match kind {
+                MouseEventKind::Down(MouseButton::Left)
+                    if contains(languages_area, column, row) =>
+                {
+                    let idx = self.offset + (row - languages_area.y) as usize;
+                    if idx < self.filtered.len() {
+                        self.focus = Focus::Languages;
+                        self.cursor = idx;
+                        self.toggle(idx);
+                    }
+                }
+                MouseEventKind::Down(MouseButton::Left) if contains(sets_area, column, row) => {
+                    let idx = (row - sets_area.y) as usize;
+                    if idx < self.sets.len() {
+                        self.focus = Focus::Sets;
+                        self.set_cursor = idx;
+                        self.apply_set(idx);
+                    }
+                }
+                MouseEventKind::ScrollUp => self.move_cursor(false, 1),
+                MouseEventKind::ScrollDown => self.move_cursor(true, 1),
+                _ => {}
+            },
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+fn highlight(row: Row, highlighted: bool) -> Row {
+    if highlighted {
+        row.style(Style::default().add_modifier(Modifier::REVERSED))
+    } else {
+        row
+    }
+}
+
+/// Lets the player choose the languages to play with from `languages`, starting with `selection`.
+///
+/// At least `options` languages need to be selected, or none to play with all of them. Named sets
+/// of languages can be saved to and loaded from `sets`, which is only changed if the player does
+/// not cancel.
+///
+/// Returns the new selection or `None` if the player cancelled.
+pub fn pick<B: Backend>(
+    terminal: &mut Terminal<B>,
+    languages: &[Language],
+    selection: &[String],
+    sets: &mut BTreeMap<String, Vec<String>>,
+    options: u8,
+) -> Result<Option<Vec<String>>> {
+    let mut picker = Picker {
+        languages,
+        sets: sets.clone(),
+        options,
+        selection: selection.iter().cloned().collect(),
+        search: String::new(),
+        filtered: Vec::new(),
+        focus: Focus::Languages,
+        cursor: 0,
+        offset: 0,
+        set_cursor: 0,
+        naming: None,
+        error: None,
+    };
+    picker.update_filter();
+
+    loop {
+        let mut languages_area = Rect::default();
+        let mut sets_area = Rect::default();
+        terminal.draw(|f| {
+            let vertical = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                ])
+                .split(f.size());
+            let search = Paragraph::new(format!("{}_", picker.search))
+                .block(Block::default().title("Search").borders(Borders::ALL));
+            f.render_widget(search, vertical[0]);
+
+            let horizontal = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(24)])
+                .split(vertical[1]);
+
+            let block = Block::default()
+                .title(format!(
+                    "Languages {}/{}",
+                    picker.filtered.len(),
+                    picker.languages.len()
+                ))
+                .borders(Borders::ALL);
+            languages_area = block.inner(horizontal[0]);
+            let visible = languages_area.height as usize;
+            picker.offset = scroll_offset(
+                picker.offset,
+                Some(picker.cursor),
+                visible,
+                picker.filtered.len(),
+            );
+            let rows = picker
+                .filtered
+                .iter()
+                .enumerate()
+                .skip(picker.offset)
+                .take(visible)
+                .map(|(idx, &language)| {
+                    let language = &picker.languages[language];
+                    let checked = if picker.selection.contains(&language.name) {
+                        "[x]"
+                    } else {
+                        "[ ]"
+                    };
+                    let dot = Cell::from("●").style(
+                        Style::default().fg(language
+                            .color
+                            .as_deref()
+                            .and_then(color)
+                            .unwrap_or(Color::Reset)),
+                    );
+                    let row = Row::new(vec![
+                        Cell::from(checked),
+                        dot,
+                        Cell::from(language.name.as_str()),
+                        Cell::from(language.kind.as_str()),
+                        Cell::from(language.group.as_deref().unwrap_or_default()),
+                    ]);
+                    highlight(
+                        row,
+                        picker.focus == Focus::Languages && idx == picker.cursor,
+                    )
+                });
+            let table = Table::new(rows)
+                .widths(&[
+                    Constraint::Length(3),
+                    Constraint::Length(1),
+                    Constraint::Percentage(50),
+                    Constraint::Length(11),
+                    Constraint::Percentage(50),
+                ])
+                .block(block);
+            f.render_widget(table, horizontal[0]);
+
+            let block = Block::default().title("Sets").borders(Borders::ALL);
+            sets_area = block.inner(horizontal[1]);
+            let rows = picker
+                .sets
+                .iter()
+                .enumerate()
+                .map(|(idx, (name, languages))| {
+                    let row = Row::new(vec![name.clone(), languages.len().to_string()]);
+                    highlight(row, picker.focus == Focus::Sets && idx == picker.set_cursor)
+                });
+            let table = Table::new(rows)
+                .widths(&[Constraint::Percentage(100), Constraint::Length(3)])
+                .block(block);
+            f.render_widget(table, horizontal[1]);
+
+            let status = if let Some(name) = &picker.naming {
+                Paragraph::new(format!("Name of the set: {}_", name))
+            } else if let Some(error) = &picker.error {
+                Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red))
+            } else if picker.selection.is_empty() {
+                Paragraph::new("Nothing selected, all languages will be used")
+            } else {
+                Paragraph::new(format!("{} selected", picker.selection.len()))
+            };
+            f.render_widget(status, vertical[2]);
+
+            let help = if picker.naming.is_some() {
+                "Enter save, Esc cancel"
+            } else {
+                "Type to search, ↑/↓ move, Enter toggle/apply set, Tab switch to sets, \
+                Del delete set, Ctrl+S save selection as set, Esc done, Ctrl+C cancel"
+            };
+            f.render_widget(Paragraph::new(help), vertical[3]);
+        })?;
+
+        match picker.handle(event::read()?, languages_area, sets_area) {
+            Action::None => {}
+            Action::Done => {
+                *sets = picker.sets;
+                return Ok(Some(picker.selection.into_iter().collect()));
+            }
+            Action::Cancel => return Ok(None),
+        }
+    }
+}
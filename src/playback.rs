@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use crossterm::event::{self, Event, KeyCode as Key, KeyEvent, KeyModifiers};
+use language_guesser::clock::ManualClock;
+use language_guesser::engine::Game;
+use language_guesser::replay::Replay;
+use tui::backend::Backend;
+use tui::Terminal;
+
+use crate::ui::{draw_game, OptionsState};
+
+/// How long a frame of the playback is shown at most
+const FRAME: Duration = Duration::from_millis(16);
+
+/// Plays `replay` again, `speed` times as fast as it happened. The player can pause it, change the
+/// speed or stop it.
+///
+/// Returns the game as it was at the end of the playback.
+pub fn show<B: Backend>(terminal: &mut Terminal<B>, replay: &Replay, speed: f64) -> Result<Game> {
+    let clock = Arc::new(ManualClock::default());
+    let mut game = Game::new(replay.settings.clone())
+        .clock(clock.clone())
+        .seed(replay.seed);
+    let mut speed = speed;
+    let mut paused = false;
+    for (idx, round) in replay.rounds.iter().enumerate() {
+        game.start_round(round.code.clone());
+        let origin = game.current().map(|current| current.origin());
+        if origin != Some(round.origin) {
+            bail!(
+                "Round {} of the replay does not match this version of the game!",
+                idx + 1
+            );
+        }
+        let mut options_state = OptionsState::default();
+        let mut code_scroll = 0;
+        // the time passed in the round so far
+        let mut at = Duration::ZERO;
+        let mut events = round.events.iter().peekable();
+        let mut last_frame = Instant::now();
+        while game.current().is_some() {
+            let help = format!(
+                "Replay of round {}/{} at {}× speed{}.\n\
+                Space to pause, +/- to change the speed, q or Esc to stop.",
+                idx + 1,
+                replay.rounds.len(),
+                speed,
+                if paused { ", paused" } else { "" }
+            );
+            terminal.draw(|f| {
+                draw_game(f, &game, &help, &mut options_state, &mut code_scroll);
+            })?;
+
+            if event::poll(FRAME)? {
+                if let Event::Key(KeyEvent {
+                    code, modifiers, ..
+                }) = event::read()?
+                {
+                    match (code, modifiers) {
+                        (Key::Char('c'), KeyModifiers::CONTROL)
+                        | (Key::Char('q'), _)
+                        | (Key::Esc, _) => return Ok(game),
+                        (Key::Char(' '), _) => paused = !paused,
+                        (Key::Char('+'), _) => speed = (speed * 2.).min(64.),
+                        (Key::Char('-'), _) => speed = (speed / 2.).max(0.25),
+                        _ => {}
+                    }
+                }
+            }
+
+            let passed = last_frame.elapsed();
+            last_frame = Instant::now();
+            if paused {
+                continue;
+            }
+            let until = at + passed.mul_f64(speed);
+            // the clock is moved to the exact time of every event, so it is handled like it was
+            while let Some(timed) = events.peek().filter(|timed| timed.at <= until) {
+                clock.advance(timed.at.saturating_sub(at));
+                at = at.max(timed.at);
+                game.handle(timed.event);
+                events.next();
+            }
+            if events.peek().is_none() {
+                // the round ended with the last event, if it did not the replay was cut off
+                break;
+            }
+            clock.advance(until - at);
+            at = until;
+        }
+    }
+    Ok(game)
+}
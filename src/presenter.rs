@@ -0,0 +1,754 @@
+use std::iter;
+use std::num::NonZeroU8;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use crossterm::event::{
+    self, Event, EventStream, KeyCode as Key, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind,
+};
+use futures_util::StreamExt;
+use language_guesser::engine::Event as GameEvent;
+use language_guesser::net::{self, ClientMessage, ServerMessage, Standing, Status};
+use language_guesser::quiz::{Quiz, QuizRound};
+use language_guesser::settings::Settings;
+use language_guesser::{Code, CodeProvider};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::{self, MissedTickBehavior};
+use tui::backend::Backend;
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Row, Table, Wrap};
+use tui::{Frame, Terminal};
+
+use crate::lan::{self, Joined, Writers};
+use crate::ui::{contains, draw_code, HOTKEYS};
+
+/// How many participants are shown between the rounds
+const LEADERBOARD: usize = 10;
+/// How high the boxes of the options are
+const OPTION_HEIGHT: u16 = 3;
+/// How high the podium is, the winner stands on all of it
+const PODIUM_HEIGHT: u16 = 9;
+const PODIUM_WIDTH: u16 = 24;
+
+/// The quiz as the presenter runs it, from the lobby until it ends
+struct Presenter {
+    settings: Settings,
+    seed: Option<u64>,
+    port: u16,
+    names: Vec<String>,
+    writers: Writers,
+    /// Which participants left, before the quiz started as well
+    left: Vec<bool>,
+    /// Created once the presenter starts the first round
+    quiz: Option<Quiz>,
+    /// The number of the round being played, or of the last one
+    number: usize,
+    /// Whether the presenter asked for the next round and it waits for its code
+    loading: bool,
+    code_scroll: u16,
+}
+
+impl Presenter {
+    fn is_running(&self) -> bool {
+        matches!(&self.quiz, Some(quiz) if quiz.is_running())
+    }
+
+    /// How many participants did not leave
+    fn remaining(&self) -> usize {
+        self.left.iter().filter(|&&left| !left).count()
+    }
+
+    fn standings(&self) -> Vec<Standing> {
+        match &self.quiz {
+            Some(quiz) => net::quiz_standings(quiz, &self.names),
+            None => self
+                .names
+                .iter()
+                .zip(&self.left)
+                .map(|(name, &left)| Standing {
+                    name: name.clone(),
+                    points: 0,
+                    lives: 0,
+                    status: if left { Status::Out } else { Status::Guessing },
+                })
+                .collect(),
+        }
+    }
+
+    async fn broadcast_standings(&mut self) {
+        let standings = self.standings();
+        lan::broadcast(&mut self.writers, &ServerMessage::Scoreboard { standings }).await;
+    }
+
+    /// Lets `joined` take part, as long as the quiz did not start yet
+    async fn join(
+        &mut self,
+        mut joined: Joined,
+        sender: &mpsc::UnboundedSender<(usize, Option<ClientMessage>)>,
+    ) {
+        if self.quiz.is_some() {
+            // dropping the connection tells them they are too late
+            return;
+        }
+        let participant = self.names.len();
+        let welcome = ServerMessage::Welcome {
+            player: participant,
+            settings: self.settings.clone(),
+        };
+        if net::send(&mut joined.writer, &welcome).await.is_err() {
+            return;
+        }
+        self.names.push(joined.name.clone());
+        self.left.push(false);
+        self.writers
+            .push(Some(lan::forward(participant, joined, sender.clone())));
+        self.broadcast_standings().await;
+    }
+
+    /// Applies what `participant` sent, returns whether the round ended
+    fn receive(&mut self, participant: usize, message: Option<ClientMessage>) -> bool {
+        let left = matches!(message, None | Some(ClientMessage::Quit));
+        if left {
+            self.left[participant] = true;
+        }
+        let quiz = match &mut self.quiz {
+            Some(quiz) => quiz,
+            None => return false,
+        };
+        match message {
+            Some(ClientMessage::Answer { round, answer }) => {
+                round == self.number && quiz.answer(participant, answer)
+            }
+            _ if left => quiz.leave(participant),
+            _ => false,
+        }
+    }
+
+    /// Lets time pass in the round being played, returns whether it ended
+    fn tick(&mut self) -> bool {
+        match &mut self.quiz {
+            Some(quiz) if quiz.is_running() => quiz.handle(GameEvent::Tick),
+            _ => false,
+        }
+    }
+
+    /// Starts the quiz with everyone that joined, `false` if nobody is there to take part
+    fn start(&mut self) -> bool {
+        if self.remaining() == 0 {
+            return false;
+        }
+        let mut quiz = Quiz::new(self.settings.clone(), self.names.len());
+        if let Some(seed) = self.seed {
+            quiz = quiz.seed(seed);
+        }
+        for (participant, &left) in self.left.iter().enumerate() {
+            if left {
+                quiz.leave(participant);
+            }
+        }
+        self.quiz = Some(quiz);
+        true
+    }
+
+    async fn start_round(&mut self, code: Code) {
+        let quiz = self.quiz.as_mut().expect("the quiz started");
+        quiz.start_round(code);
+        self.number += 1;
+        self.code_scroll = 0;
+        let code = quiz
+            .current()
+            .expect("a participant is still in the quiz")
+            .code()
+            .clone();
+        let number = self.number;
+        lan::broadcast(&mut self.writers, &ServerMessage::Round { number, code }).await;
+        self.broadcast_standings().await;
+    }
+
+    async fn end_round(&mut self) {
+        let standings = self.standings();
+        lan::broadcast(&mut self.writers, &ServerMessage::RoundOver { standings }).await;
+    }
+
+    /// Draws the lobby, the round being played or the answer of the last one.
+    ///
+    /// Returns where the code was drawn to map mouse events to it.
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>) -> Rect {
+        let vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(f.size());
+        let standings = self.standings();
+        let (help, code_area) = match &self.quiz {
+            Some(quiz) if quiz.is_running() => {
+                let code_area =
+                    draw_round(f, vertical[0], quiz, self.number, &mut self.code_scroll);
+                ("↑/↓ scroll the code, CTRL+C end the quiz", code_area)
+            }
+            // the quiz starts with loading the first round, the lobby stays until it is there
+            Some(quiz) if self.number > 0 => {
+                draw_answer(f, vertical[0], quiz.rounds().last(), self.number, standings);
+                let help = if self.loading {
+                    "Loading the next round..., q end the quiz"
+                } else {
+                    "Enter next round, q end the quiz"
+                };
+                (help, Rect::default())
+            }
+            _ => {
+                self.draw_lobby(f, vertical[0]);
+                let help = if self.loading {
+                    "Loading the first round..., q quit"
+                } else {
+                    "Enter start the quiz, q quit"
+                };
+                (help, Rect::default())
+            }
+        };
+        f.render_widget(Paragraph::new(help), vertical[1]);
+        code_area
+    }
+
+    fn draw_lobby<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let names: Vec<_> = self
+            .names
+            .iter()
+            .zip(&self.left)
+            .filter(|(_, &left)| !left)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let text = vec![
+            Spans::from(""),
+            Spans::from(Span::styled("Join the quiz with", bold)),
+            Spans::from(""),
+            Spans::from(Span::styled(
+                format!(
+                    "{} join <this machine>:{}",
+                    env!("CARGO_PKG_NAME"),
+                    self.port
+                ),
+                bold.fg(Color::Yellow),
+            )),
+            Spans::from(""),
+            Spans::from(match names.len() {
+                0 => String::from("Nobody joined yet."),
+                1 => String::from("1 participant:"),
+                count => format!("{} participants:", count),
+            }),
+            Spans::from(names.join(", ")),
+        ];
+        let paragraph = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Quiz").borders(Borders::ALL));
+        f.render_widget(paragraph, area);
+    }
+}
+
+/// Draws the round of `quiz` being played with the options as big boxes, to be read from the back
+/// of the room.
+///
+/// Returns where the code was drawn to map mouse events to it.
+fn draw_round<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    quiz: &Quiz,
+    number: usize,
+    code_scroll: &mut u16,
+) -> Rect {
+    let current = quiz.current().expect("a round is being played");
+    let options = &current.code().options;
+    // up to 4 options fit next to each other, more are split into 2 rows
+    let columns = if options.len() <= 4 {
+        options.len().max(1)
+    } else {
+        options.len() - options.len() / 2
+    };
+    let rows = if options.len() > columns { 2 } else { 1 };
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(rows as u16 * OPTION_HEIGHT),
+        ])
+        .split(area);
+    let (answered, total) = quiz.answered();
+    let header = Paragraph::new(Span::styled(
+        format!(
+            "Round {}   ·   {} points   ·   {} of {} answered",
+            number,
+            quiz.round_points(),
+            answered,
+            total
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, vertical[0]);
+    draw_code(f, current, vertical[1], code_scroll, "");
+
+    let row_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(OPTION_HEIGHT); rows])
+        .split(vertical[2]);
+    for (row, row_area) in row_areas.into_iter().enumerate() {
+        let cells = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+            .split(row_area);
+        for (column, cell) in cells.into_iter().enumerate() {
+            let idx = row * columns + column;
+            if let Some(option) = options.get(idx) {
+                let text = Spans::from(vec![
+                    Span::styled(
+                        format!("{}  ", HOTKEYS[idx]),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        option.as_str(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                ]);
+                let paragraph = Paragraph::new(text)
+                    .alignment(Alignment::Center)
+                    .block(Block::default().borders(Borders::ALL));
+                f.render_widget(paragraph, cell);
+            }
+        }
+    }
+    vertical[1]
+}
+
+/// Draws the answer of the last `round`, how the participants answered and who is ahead
+fn draw_answer<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    round: Option<&QuizRound>,
+    number: usize,
+    mut standings: Vec<Standing>,
+) {
+    let round = match round {
+        Some(round) => round,
+        None => {
+            let paragraph = Paragraph::new("\nNobody took part in the last round.")
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            f.render_widget(paragraph, area);
+            return;
+        }
+    };
+    let code = &round.code;
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(code.options.len() as u16 + 3),
+            Constraint::Min(0),
+        ])
+        .split(area);
+    let correct = round.distribution()[code.language];
+    let header = Paragraph::new(Spans::from(vec![
+        Span::raw(format!("Round {}: it was ", number)),
+        Span::styled(
+            code.options[code.language].as_str(),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!(
+            ", {} of {} got it right",
+            correct,
+            round.answers.iter().flatten().count()
+        )),
+    ]))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, vertical[0]);
+
+    let block = Block::default().title("Answers").borders(Borders::ALL);
+    let bars = Paragraph::new(distribution(round, block.inner(vertical[1]).width)).block(block);
+    f.render_widget(bars, vertical[1]);
+
+    standings.sort_by_key(|standing| -standing.points);
+    let rows = standings
+        .iter()
+        .take(LEADERBOARD)
+        .enumerate()
+        .map(|(idx, standing)| {
+            Row::new(vec![
+                (idx + 1).to_string(),
+                standing.name.clone(),
+                standing.points.to_string(),
+                standing.status.to_string(),
+            ])
+        });
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec!["#", "Participant", "Points", "This round"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .widths(&[
+            Constraint::Length(3),
+            Constraint::Length(20),
+            Constraint::Length(7),
+            Constraint::Min(0),
+        ])
+        .block(Block::default().title("Leaderboard").borders(Borders::ALL));
+    f.render_widget(table, vertical[2]);
+}
+
+/// A bar for every option of `round` with how many participants chose it, the correct one in
+/// green, and one for those that did not answer, all fitting into `width` columns
+fn distribution(round: &QuizRound, width: u16) -> Vec<Spans<'static>> {
+    let options = &round.code.options;
+    let counts = round.distribution();
+    let unanswered = round.unanswered();
+    let most = counts
+        .iter()
+        .copied()
+        .chain(iter::once(unanswered))
+        .max()
+        .unwrap_or_default();
+    let name_width = options
+        .iter()
+        .map(|option| option.chars().count())
+        .chain(iter::once("no answer".len() - 2))
+        .max()
+        .unwrap_or_default();
+    // the hotkey in front of the name and the count after the bar
+    let bar_width = (width as usize).saturating_sub(name_width + 8);
+    let bar = |count: usize| "█".repeat(count * bar_width / most.max(1));
+    let mut lines: Vec<_> = options
+        .iter()
+        .zip(&counts)
+        .enumerate()
+        .map(|(idx, (option, &count))| {
+            let style = if idx == round.code.language {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            Spans::from(vec![
+                Span::styled(
+                    format!("{} {:<width$} ", HOTKEYS[idx], option, width = name_width),
+                    style,
+                ),
+                Span::styled(bar(count), style),
+                Span::styled(format!(" {}", count), style),
+            ])
+        })
+        .collect();
+    let style = Style::default().fg(Color::DarkGray);
+    lines.push(Spans::from(vec![
+        Span::styled(
+            format!("{:<width$} ", "no answer", width = name_width + 2),
+            style,
+        ),
+        Span::styled(bar(unanswered), style),
+        Span::styled(format!(" {}", unanswered), style),
+    ]));
+    lines
+}
+
+/// Runs a quiz on `port`: participants join with [`lan::join`] while the projector shows how, then
+/// the presenter starts every round when the room is ready. The code is taken from
+/// `code_provider`, reproducibly if there is a `seed`.
+///
+/// Returns the final standings. The screen is redrawn at most `fps` times per second and only if
+/// something on it changed.
+pub async fn present<B: Backend>(
+    terminal: &mut Terminal<B>,
+    settings: Settings,
+    seed: Option<u64>,
+    code_provider: Arc<Box<dyn CodeProvider>>,
+    port: u16,
+    fps: NonZeroU8,
+) -> Result<Vec<Standing>> {
+    lan::validate(&settings)?;
+    let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    // connections are accepted on their own so the projector keeps being drawn meanwhile
+    let (join_sender, mut joins) = mpsc::unbounded_channel();
+    let accept = tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let sender = join_sender.clone();
+            tokio::spawn(async move {
+                if let Some(joined) = lan::handshake(stream).await {
+                    sender.send(joined).ok();
+                }
+            });
+        }
+    });
+    let (sender, mut messages) = mpsc::unbounded_channel();
+
+    let mut presenter = Presenter {
+        settings,
+        seed,
+        port,
+        names: Vec::new(),
+        writers: Writers::new(),
+        left: Vec::new(),
+        quiz: None,
+        number: 0,
+        loading: false,
+        code_scroll: 0,
+    };
+    let c = code_provider.clone();
+    let mut next = Box::pin(tokio::spawn(async move { c.get_code().await }));
+    let mut code_area = Rect::default();
+    let mut events = EventStream::new();
+    let mut frames = time::interval(Duration::from_secs(1) / fps.get().into());
+    frames.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut drawn_points = None;
+    let mut changed = true;
+    loop {
+        let event = tokio::select! {
+            _ = frames.tick() => {
+                if presenter.tick() {
+                    presenter.end_round().await;
+                    changed = true;
+                }
+                let points = presenter
+                    .quiz
+                    .as_ref()
+                    .filter(|quiz| quiz.is_running())
+                    .map(Quiz::round_points);
+                if changed || points != drawn_points {
+                    terminal.draw(|f| code_area = presenter.draw(f))?;
+                    drawn_points = points;
+                    changed = false;
+                }
+                continue;
+            }
+            Some(joined) = joins.recv() => {
+                presenter.join(joined, &sender).await;
+                changed = true;
+                continue;
+            }
+            // the projector keeps being drawn while the code of the next round is fetched
+            code = &mut next, if presenter.loading => {
+                presenter.loading = false;
+                if presenter.remaining() == 0 {
+                    break;
+                }
+                let c = code_provider.clone();
+                next = Box::pin(tokio::spawn(async move { c.get_code().await }));
+                presenter.start_round(code??).await;
+                changed = true;
+                continue;
+            }
+            Some((participant, message)) = messages.recv() => {
+                let ended = presenter.receive(participant, message);
+                presenter.broadcast_standings().await;
+                if ended {
+                    presenter.end_round().await;
+                }
+                changed = true;
+                continue;
+            }
+            event = events.next() => match event {
+                Some(event) => event?,
+                None => bail!("The terminal stopped sending input!"),
+            },
+        };
+        // moving the mouse changes nothing on the screen, everything else might
+        if let Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Moved,
+            ..
+        }) = event
+        {
+            continue;
+        }
+        changed = true;
+
+        match event {
+            Event::Key(KeyEvent {
+                code: key,
+                modifiers,
+                ..
+            }) => match (key, modifiers) {
+                (Key::Char('c'), KeyModifiers::CONTROL) => break,
+                (Key::Char('q'), _) | (Key::Esc, _) if !presenter.is_running() => break,
+                (Key::Enter, _) | (Key::Char(' '), _)
+                    if !presenter.is_running() && !presenter.loading =>
+                {
+                    if presenter.quiz.is_none() && !presenter.start() {
+                        continue;
+                    }
+                    if presenter.remaining() == 0 {
+                        break;
+                    }
+                    presenter.loading = true;
+                }
+                (Key::Up, _) | (Key::Char('k'), _) => {
+                    presenter.code_scroll = presenter.code_scroll.saturating_sub(1)
+                }
+                (Key::Down, _) | (Key::Char('j'), _) => presenter.code_scroll += 1,
+                _ => {}
+            },
+            Event::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) if contains(code_area, column, row) => match kind {
+                MouseEventKind::ScrollUp => {
+                    presenter.code_scroll = presenter.code_scroll.saturating_sub(1)
+                }
+                MouseEventKind::ScrollDown => presenter.code_scroll += 1,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    // the results are shown without the stream, it would take the input away from them
+    drop(events);
+    accept.abort();
+
+    let standings = presenter.standings();
+    lan::broadcast(
+        &mut presenter.writers,
+        &ServerMessage::GameOver {
+            standings: standings.clone(),
+        },
+    )
+    .await;
+    if let Some(quiz) = &presenter.quiz {
+        show(terminal, quiz, &presenter.names)?;
+    }
+    Ok(standings)
+}
+
+/// Shows the best three participants of `quiz` on a podium and how everyone answered every round,
+/// `names` being the names of the participants
+fn show<B: Backend>(terminal: &mut Terminal<B>, quiz: &Quiz, names: &[String]) -> Result<()> {
+    let points = quiz.points();
+    let mut ranking: Vec<_> = (0..names.len()).collect();
+    ranking.sort_by_key(|&participant| -points[participant]);
+    let rounds = quiz.rounds();
+    let mut offset = 0;
+    loop {
+        let mut visible = 0;
+        terminal.draw(|f| {
+            let vertical = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(PODIUM_HEIGHT),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                ])
+                .split(f.size());
+            let places = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Min(0),
+                    Constraint::Length(PODIUM_WIDTH),
+                    Constraint::Length(PODIUM_WIDTH),
+                    Constraint::Length(PODIUM_WIDTH),
+                    Constraint::Min(0),
+                ])
+                .split(vertical[0]);
+            // the winner stands in the middle, the second on the left and the third on the right
+            for (place, (&participant, area)) in ranking
+                .iter()
+                .zip(&[places[2], places[1], places[3]])
+                .enumerate()
+            {
+                let height = (PODIUM_HEIGHT - 2 * place as u16).min(area.height);
+                let area = Rect::new(area.x, area.bottom() - height, area.width, height);
+                let text = vec![
+                    Spans::from(Span::styled(
+                        names[participant].as_str(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )),
+                    Spans::from(format!("{} points", points[participant])),
+                ];
+                let paragraph = Paragraph::new(text).alignment(Alignment::Center).block(
+                    Block::default()
+                        .title(format!("{}.", place + 1))
+                        .borders(Borders::ALL),
+                );
+                f.render_widget(paragraph, area);
+            }
+
+            let block = Block::default().title("Rounds").borders(Borders::ALL);
+            // the header takes up the first row
+            visible = block.inner(vertical[1]).height.saturating_sub(1) as usize;
+            offset = offset.min(rounds.len().saturating_sub(visible));
+            let rows = rounds
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(visible)
+                .map(|(idx, round)| {
+                    let code = &round.code;
+                    let counts = round.distribution();
+                    let mut answers: Vec<_> = code
+                        .options
+                        .iter()
+                        .zip(&counts)
+                        .filter(|(_, &count)| count > 0)
+                        .map(|(option, count)| format!("{} {}", option, count))
+                        .collect();
+                    if round.unanswered() > 0 {
+                        answers.push(format!("no answer {}", round.unanswered()));
+                    }
+                    Row::new(vec![
+                        (idx + 1).to_string(),
+                        code.options[code.language].clone(),
+                        format!(
+                            "{} of {}",
+                            counts[code.language],
+                            round.answers.iter().flatten().count()
+                        ),
+                        answers.join(", "),
+                    ])
+                });
+            let table = Table::new(rows)
+                .header(
+                    Row::new(vec!["#", "Language", "Correct", "Answers"])
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                )
+                .widths(&[
+                    Constraint::Length(3),
+                    Constraint::Length(20),
+                    Constraint::Length(10),
+                    Constraint::Min(0),
+                ])
+                .block(block);
+            f.render_widget(table, vertical[1]);
+
+            let help = "↑/↓ scroll, q quit";
+            f.render_widget(Paragraph::new(help), vertical[2]);
+        })?;
+
+        match event::read()? {
+            Event::Key(KeyEvent {
+                code: key,
+                modifiers,
+                ..
+            }) => match (key, modifiers) {
+                (Key::Char('c'), KeyModifiers::CONTROL)
+                | (Key::Char('q'), _)
+                | (Key::Esc, _)
+                | (Key::Enter, _) => return Ok(()),
+                (Key::Up, _) | (Key::Char('k'), _) => offset = offset.saturating_sub(1),
+                (Key::Down, _) | (Key::Char('j'), _) => offset += 1,
+                (Key::PageUp, _) => offset = offset.saturating_sub(visible),
+                (Key::PageDown, _) => offset += visible,
+                _ => {}
+            },
+            Event::Mouse(MouseEvent { kind, .. }) => match kind {
+                MouseEventKind::ScrollUp => offset = offset.saturating_sub(1),
+                MouseEventKind::ScrollDown => offset += 1,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
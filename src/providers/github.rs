@@ -1,8 +1,16 @@
-use crate::{util::DeserializeKeys, Code, CodeProvider};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{
+    linguist::LANGUAGES_URL,
+    sampler::{Sampler, Uniform},
+    util::DeserializeKeys,
+    Code, CodeProvider,
+};
 use anyhow::{anyhow, bail, Result};
 use async_trait::async_trait;
 use octocrab::Octocrab;
-use rand::{prelude::*, thread_rng};
+use rand::prelude::*;
+use rand::rngs::StdRng;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -15,16 +23,16 @@ pub struct GitHub {
     language_count: usize,
     retries: u8,
     languages: Vec<String>,
+    sampler: Arc<dyn Sampler>,
+    /// Only locked while choosing, never across a request
+    rng: Arc<Mutex<StdRng>>,
 }
 
 impl GitHub {
     pub async fn new(mut languages: Vec<String>) -> Result<Self> {
         if languages.is_empty() {
             let res = octocrab::instance()
-                ._get(
-                    "https://raw.githubusercontent.com/github/linguist/master/lib/linguist/languages.yml",
-                    None::<&()>,
-                )
+                ._get(LANGUAGES_URL, None::<&()>)
                 .await?
                 .bytes()
                 .await?;
@@ -38,9 +46,15 @@ impl GitHub {
             language_count: 4,
             retries: 8,
             languages,
+            sampler: Arc::new(Uniform),
+            rng: Arc::new(Mutex::new(StdRng::from_entropy())),
         })
     }
 
+    fn rng(&self) -> MutexGuard<'_, StdRng> {
+        self.rng.lock().expect("the rng is never poisoned")
+    }
+
     pub fn token(self, token: Option<String>) -> Result<Self> {
         if let Some(token) = token {
             octocrab::initialise(Octocrab::builder().personal_token(token))?;
@@ -61,12 +75,9 @@ impl CodeProvider for GitHub {
         let octocrab = octocrab::instance();
         for _ in 0..self.retries {
             let _: Result<()> = try {
-                let languages: Vec<String> = self
-                    .languages
-                    .choose_multiple(&mut thread_rng(), self.language_count)
-                    .map(|s| s.to_string())
-                    .collect();
-                let idx = thread_rng().gen_range(0..languages.len());
+                let (languages, idx) =
+                    self.sampler
+                        .sample(&mut *self.rng(), &self.languages, self.language_count);
                 let language = &languages[idx];
 
                 let repos = octocrab
@@ -77,7 +88,7 @@ impl CodeProvider for GitHub {
                     .await?
                     .items;
 
-                let repo = repos.choose(&mut thread_rng()).ok_or_else(|| anyhow!(""))?;
+                let repo = repos.choose(&mut *self.rng()).ok_or_else(|| anyhow!(""))?;
 
                 let files = octocrab
                     .search()
@@ -86,7 +97,7 @@ impl CodeProvider for GitHub {
                     .await?
                     .items;
 
-                let file = files.choose(&mut thread_rng()).ok_or_else(|| anyhow!(""))?;
+                let file = files.choose(&mut *self.rng()).ok_or_else(|| anyhow!(""))?;
 
                 let code: CodeRequest = octocrab.get(&file.url, None::<&()>).await?;
                 let code: String = octocrab
@@ -99,6 +110,7 @@ impl CodeProvider for GitHub {
                     code: code.max(" ".to_string()),
                     language: idx,
                     options: languages,
+                    origin: None,
                 });
             };
         }
@@ -112,4 +124,12 @@ impl CodeProvider for GitHub {
     fn options(&mut self, count: u8) {
         self.language_count = count.into();
     }
+
+    fn sampler(&mut self, sampler: Arc<dyn Sampler>) {
+        self.sampler = sampler;
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.rng = Arc::new(Mutex::new(StdRng::seed_from_u64(seed)));
+    }
 }
@@ -1,9 +1,13 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use async_trait::async_trait;
 
+use crate::sampler::Sampler;
 use crate::{Code, CodeProvider};
 
 pub mod github;
+pub mod pack;
 
 #[derive(Default)]
 pub struct TestProvider {
@@ -17,16 +21,19 @@ impl CodeProvider for TestProvider {
             reference: "test".into(),
             code: "ABC".into(),
             language: 0,
-            options: vec!["a", "b", "c", "d"]
-                .into_iter()
-                .take(self.options)
-                .map(&str::to_string)
+            options: (0..self.options)
+                .map(|idx| format!("language {}", idx + 1))
                 .collect(),
+            origin: None,
         })
     }
 
     fn retries(&mut self, _count: u8) {}
 
+    fn sampler(&mut self, _sampler: Arc<dyn Sampler>) {}
+
+    fn seed(&mut self, _seed: u64) {}
+
     fn options(&mut self, count: u8) {
         self.options = count.into();
     }
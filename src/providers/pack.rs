@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Round;
+use crate::sampler::Sampler;
+use crate::{Code, CodeProvider, MAX_OPTIONS};
+
+/// The snippets shipped with the game, used when no other pack is given
+const BUNDLED_PACK: &str = include_str!("pack.yml");
+/// The packs exported from played games
+const PACKS_DIR: &str = "packs";
+
+/// A piece of code in a pack
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Snippet {
+    /// Where the code comes from
+    pub reference: String,
+    pub code: String,
+    pub language: String,
+    /// The languages to choose from, picked from the other languages in the pack if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    /// The grapheme the reveal starts at, random if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<usize>,
+}
+
+/// A fixed set of snippets, so everyone playing it gets the same code
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Pack {
+    pub snippets: Vec<Snippet>,
+}
+
+impl Pack {
+    /// Loads the pack at `path` (YAML or JSON), or the bundled one if there is no path
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let pack: Pack = match path {
+            Some(path) => serde_yaml::from_slice(&fs::read(path)?)?,
+            None => serde_yaml::from_str(BUNDLED_PACK)?,
+        };
+        if pack.snippets.is_empty() {
+            bail!("The pack does not contain any snippets!");
+        }
+        // there is a key for every option, but not for more
+        let too_many = pack
+            .snippets
+            .iter()
+            .find(|snippet| snippet.options.len() > MAX_OPTIONS);
+        if let Some(snippet) = too_many {
+            bail!(
+                "{} has {} options, too many! Can be at most {}",
+                snippet.reference,
+                snippet.options.len(),
+                MAX_OPTIONS
+            );
+        }
+        Ok(pack)
+    }
+
+    /// The code, options and reveal of `rounds` in the order they were played, so others can play
+    /// exactly the same game
+    pub fn from_rounds(rounds: &[Round]) -> Self {
+        Self {
+            snippets: rounds
+                .iter()
+                .map(|round| Snippet {
+                    reference: round.code.reference.clone(),
+                    code: round.code.code.clone(),
+                    language: round.language().to_string(),
+                    options: round.code.options.clone(),
+                    origin: round.code.origin,
+                })
+                .collect(),
+        }
+    }
+
+    /// Stores the pack of the game started at `started_at` with the other exported packs, returns
+    /// where it was stored
+    pub fn save(&self, started_at: DateTime<Local>) -> Result<PathBuf> {
+        let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+        let path = dirs.place_data_file(
+            Path::new(PACKS_DIR).join(format!("{}.yml", started_at.format("%Y-%m-%d_%H-%M-%S"))),
+        )?;
+        fs::write(&path, serde_yaml::to_string(self)?)?;
+        Ok(path)
+    }
+}
+
+/// Serves the snippets of a [`Pack`] in order, or in an order decided by the seed, starting over
+/// once all of them were played
+pub struct PackProvider {
+    snippets: Vec<Snippet>,
+    /// Every language in the pack, the options are chosen from these
+    languages: Vec<String>,
+    options: usize,
+    order: Vec<usize>,
+    next: AtomicUsize,
+    rng: Mutex<StdRng>,
+}
+
+impl PackProvider {
+    pub fn new(pack: Pack) -> Self {
+        let mut languages: Vec<String> = pack
+            .snippets
+            .iter()
+            .map(|snippet| snippet.language.clone())
+            .collect();
+        languages.sort();
+        languages.dedup();
+        Self {
+            order: (0..pack.snippets.len()).collect(),
+            snippets: pack.snippets,
+            languages,
+            options: 4,
+            next: AtomicUsize::new(0),
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    fn rng(&self) -> MutexGuard<'_, StdRng> {
+        self.rng.lock().expect("the rng is never poisoned")
+    }
+}
+
+#[async_trait]
+impl CodeProvider for PackProvider {
+    async fn get_code(&self) -> Result<Code> {
+        let next = self.next.fetch_add(1, Ordering::SeqCst);
+        let snippet = &self.snippets[self.order[next % self.order.len()]];
+        let mut options = snippet.options.clone();
+        if options.is_empty() {
+            let others: Vec<_> = self
+                .languages
+                .iter()
+                .filter(|&language| language != &snippet.language)
+                .collect();
+            options = others
+                .choose_multiple(&mut *self.rng(), self.options.saturating_sub(1))
+                .map(|&other| other.clone())
+                .collect();
+            options.push(snippet.language.clone());
+            options.shuffle(&mut *self.rng());
+        } else if !options.contains(&snippet.language) {
+            bail!(
+                "The options of {} do not contain its language {}!",
+                snippet.reference,
+                snippet.language
+            );
+        }
+        Ok(Code {
+            reference: snippet.reference.clone(),
+            code: snippet.code.clone(),
+            language: options
+                .iter()
+                .position(|option| option == &snippet.language)
+                .expect("the language is one of the options"),
+            options,
+            origin: snippet.origin,
+        })
+    }
+
+    fn retries(&mut self, _count: u8) {}
+
+    fn options(&mut self, count: u8) {
+        self.options = count.into();
+    }
+
+    /// The pack decides the languages, so there is nothing to sample
+    fn sampler(&mut self, _sampler: Arc<dyn Sampler>) {}
+
+    /// Shuffles the order of the snippets
+    fn seed(&mut self, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.order.shuffle(&mut rng);
+        self.rng = Mutex::new(rng);
+    }
+}
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::clock::{Clock, SystemClock};
+use crate::engine::{Current, Event, Game, Outcome, Round};
+use crate::settings::Settings;
+use crate::Code;
+
+/// A round of a [`Quiz`] once everyone answered
+#[derive(Clone, Debug)]
+pub struct QuizRound {
+    pub code: Code,
+    /// The round of every participant, `None` for the ones that left before it started
+    pub answers: Vec<Option<Round>>,
+}
+
+impl QuizRound {
+    /// How many participants chose each option, in the order of the options
+    pub fn distribution(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.code.options.len()];
+        for answer in self
+            .answers
+            .iter()
+            .flatten()
+            .filter_map(|round| round.answer)
+        {
+            counts[answer] += 1;
+        }
+        counts
+    }
+
+    /// How many participants did not answer in time, the ones that left during the round are not
+    /// counted
+    pub fn unanswered(&self) -> usize {
+        self.answers
+            .iter()
+            .flatten()
+            .filter(|round| round.answer.is_none() && round.outcome != Outcome::Quit)
+            .count()
+    }
+}
+
+/// A quiz led by a presenter, who starts every round when the room is ready.
+///
+/// All participants answer the same code on their own and every correct answer scores, the faster
+/// the more. The streak of every participant carries over from round to round, so does its
+/// multiplier. Nobody runs out of lives, the quiz goes on until the presenter ends it.
+pub struct Quiz {
+    settings: Settings,
+    clock: Arc<dyn Clock>,
+    /// Seeds the game of every round
+    rng: StdRng,
+    points: Vec<i32>,
+    /// The correct answers of every participant in a row, every round is a new game that goes on
+    /// with it
+    streaks: Vec<usize>,
+    left: Vec<bool>,
+    /// The game of every participant in the round being played, `None` for the ones that left
+    current: Vec<Option<Game>>,
+    rounds: Vec<QuizRound>,
+}
+
+impl Quiz {
+    pub fn new(settings: Settings, participants: usize) -> Self {
+        Self {
+            settings,
+            clock: Arc::new(SystemClock),
+            rng: StdRng::from_entropy(),
+            points: vec![0; participants],
+            streaks: vec![0; participants],
+            left: vec![false; participants],
+            current: Vec::new(),
+            rounds: Vec::new(),
+        }
+    }
+
+    /// Replaces where the time is taken from
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Makes the random choices of the quiz reproducible
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// The points of every participant
+    pub fn points(&self) -> &[i32] {
+        &self.points
+    }
+
+    /// The rounds everyone answered so far
+    pub fn rounds(&self) -> &[QuizRound] {
+        &self.rounds
+    }
+
+    /// Whether a round is being played, it ends once everyone answered
+    pub fn is_running(&self) -> bool {
+        !self.current.is_empty()
+    }
+
+    /// The game of `participant` in the round being played, `None` between rounds or once they
+    /// left
+    pub fn game(&self, participant: usize) -> Option<&Game> {
+        self.current.get(participant).and_then(Option::as_ref)
+    }
+
+    pub fn has_left(&self, participant: usize) -> bool {
+        self.left[participant]
+    }
+
+    /// The round being played, as long as a participant did not answer yet
+    pub fn current(&self) -> Option<&Current> {
+        self.current.iter().flatten().find_map(Game::current)
+    }
+
+    /// How many participants answered the round being played, and how many take part in it
+    pub fn answered(&self) -> (usize, usize) {
+        let games: Vec<_> = self.current.iter().flatten().collect();
+        let answered = games.iter().filter(|game| game.current().is_none()).count();
+        (answered, games.len())
+    }
+
+    /// The points a correct answer would get right now, without the bonus
+    pub fn round_points(&self) -> i32 {
+        self.current
+            .iter()
+            .flatten()
+            .find(|game| game.current().is_some())
+            .map_or(0, Game::round_points)
+    }
+
+    /// Starts a round with `code` for every participant that did not leave, all of them see the
+    /// same part of the code
+    pub fn start_round(&mut self, mut code: Code) {
+        let seed = self.rng.gen();
+        let settings = &self.settings;
+        let clock = &self.clock;
+        self.current = self
+            .left
+            .iter()
+            .zip(&self.streaks)
+            .map(|(&left, &streak)| {
+                if left {
+                    return None;
+                }
+                let mut game = Game::new(settings.clone())
+                    .clock(clock.clone())
+                    .seed(seed)
+                    .initial_streak(streak);
+                game.start_round(code.clone());
+                code.origin = game.current().map(Current::origin);
+                Some(game)
+            })
+            .collect();
+    }
+
+    /// Applies `event` to the round of every participant, answers are given with
+    /// [`Quiz::answer`].
+    ///
+    /// Returns whether the round ended, it is the last of [`Quiz::rounds`] then.
+    pub fn handle(&mut self, event: Event) -> bool {
+        for game in self.current.iter_mut().flatten() {
+            game.handle(event);
+        }
+        self.finish()
+    }
+
+    /// `participant` chose the option `answer`, ignored if they already answered in this round.
+    ///
+    /// Returns whether the round ended.
+    pub fn answer(&mut self, participant: usize, answer: usize) -> bool {
+        if let Some(Some(game)) = self.current.get_mut(participant) {
+            game.handle(Event::Answer(answer));
+        }
+        self.finish()
+    }
+
+    /// `participant` left the quiz, they do not take part in the following rounds.
+    ///
+    /// Returns whether the round ended.
+    pub fn leave(&mut self, participant: usize) -> bool {
+        self.left[participant] = true;
+        if let Some(Some(game)) = self.current.get_mut(participant) {
+            game.handle(Event::Quit);
+        }
+        self.finish()
+    }
+
+    /// Ends the round being played once everyone answered, adding up the points
+    fn finish(&mut self) -> bool {
+        if self.current.is_empty() || self.current().is_some() {
+            return false;
+        }
+        let streaks = &mut self.streaks;
+        let answers: Vec<_> = self
+            .current
+            .drain(..)
+            .zip(streaks.iter_mut())
+            .map(|(game, streak)| {
+                game.map(|game| {
+                    *streak = game.streak();
+                    game.into_rounds().pop().expect("the round was played")
+                })
+            })
+            .collect();
+        for (points, round) in self.points.iter_mut().zip(&answers) {
+            *points += round.as_ref().map_or(0, |round| round.points);
+        }
+        let code = answers
+            .iter()
+            .flatten()
+            .next()
+            .map(|round| round.code.clone());
+        // a round without participants is not worth keeping
+        if let Some(code) = code {
+            self.rounds.push(QuizRound { code, answers });
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn code() -> Code {
+        Code {
+            reference: "test".into(),
+            code: "fn main() {}\n".into(),
+            language: 0,
+            options: vec!["Rust".into(), "C".into(), "Go".into(), "Java".into()],
+            origin: Some(0),
+        }
+    }
+
+    fn quiz(participants: usize) -> Quiz {
+        Quiz::new(Settings::default(), participants).seed(1)
+    }
+
+    #[test]
+    fn streaks_carry_over_between_rounds() {
+        let mut quiz = quiz(2);
+        quiz.start_round(code());
+        quiz.answer(0, 0);
+        assert!(quiz.answer(1, 1));
+        quiz.start_round(code());
+        quiz.answer(0, 0);
+        assert!(quiz.answer(1, 0));
+
+        let second = &quiz.rounds()[1].answers;
+        assert_eq!(second[0].as_ref().unwrap().breakdown.streak, 2);
+        assert_eq!(second[1].as_ref().unwrap().breakdown.streak, 1);
+    }
+
+    #[test]
+    fn leaving_is_not_counted_as_no_answer() {
+        let clock = Arc::new(ManualClock::default());
+        let mut quiz = quiz(3).clock(clock.clone());
+        quiz.start_round(code());
+        quiz.answer(0, 0);
+        quiz.leave(1);
+        // the third participant runs out of time
+        let mut ended = false;
+        while !ended {
+            clock.advance(Duration::from_secs(1));
+            ended = quiz.handle(Event::Tick);
+        }
+
+        let round = &quiz.rounds()[0];
+        assert_eq!(round.unanswered(), 1);
+        assert_eq!(round.distribution(), vec![1, 0, 0, 0]);
+    }
+}
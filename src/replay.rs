@@ -0,0 +1,69 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Event;
+use crate::settings::Settings;
+use crate::Code;
+
+/// The replays of all games, one file per game named after the time it started
+const REPLAYS_DIR: &str = "replays";
+
+/// Something that happened in a round and when, relative to the start of the round
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Timed {
+    pub at: Duration,
+    pub event: Event,
+}
+
+/// A round as it was played, enough to play it again
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RoundReplay {
+    pub code: Code,
+    /// The grapheme the revealed code was centered around
+    pub origin: usize,
+    /// The events that changed the round, ticks are only kept when the points decreased
+    pub events: Vec<Timed>,
+}
+
+/// Everything needed to play a game again exactly the way it happened
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Replay {
+    pub started_at: DateTime<Local>,
+    pub finished_at: DateTime<Local>,
+    pub settings: Settings,
+    /// The seed of the game, it decides what the random choices during a round are
+    pub seed: u64,
+    pub rounds: Vec<RoundReplay>,
+}
+
+impl Replay {
+    /// Stores the replay with the other replays, returns where it was stored
+    pub fn save(&self) -> Result<PathBuf> {
+        let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+        let path = dirs.place_data_file(Path::new(REPLAYS_DIR).join(format!(
+            "{}.json",
+            self.started_at.format("%Y-%m-%d_%H-%M-%S")
+        )))?;
+        fs::write(&path, serde_json::to_string(self)?)?;
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// The replay of the game played last, if any game was recorded
+    pub fn latest() -> Result<Option<Self>> {
+        let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+        // the names sort by date
+        match dirs.list_data_files(REPLAYS_DIR).into_iter().max() {
+            Some(path) => Ok(Some(Self::load(&path)?)),
+            None => Ok(None),
+        }
+    }
+}
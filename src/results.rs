@@ -0,0 +1,333 @@
+use std::io;
+use std::process::{Command, Stdio};
+
+use anyhow::Result;
+use crossterm::event::{
+    self, Event, KeyCode as Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use language_guesser::engine::{Outcome, Round};
+use language_guesser::providers::pack::Pack;
+use tui::backend::Backend;
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Row, Table, Wrap};
+use tui::Terminal;
+
+use crate::export::{summary, Report};
+use crate::highscores::{Board, HighScores, TOP};
+use crate::ui::{contains, draw_buttons, high_scores_table, scroll_offset};
+
+/// Opens `url` in the default browser
+fn open(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// The answer of the player for displaying
+pub fn answer_text(round: &Round) -> String {
+    match round.outcome {
+        Outcome::Guess => round.answered_language().unwrap_or_default().to_string(),
+        Outcome::Timeout => String::from("(timeout)"),
+        Outcome::Quit => String::from("(quit)"),
+        Outcome::Skip => String::from("(skipped)"),
+        Outcome::Beaten => String::from("(beaten)"),
+    }
+}
+
+/// The points of a round for displaying, only correct answers score
+pub fn score_text(round: &Round) -> String {
+    if round.is_correct() {
+        round.points.to_string()
+    } else {
+        String::from("---")
+    }
+}
+
+/// How long the player took to answer, like `3.4s`
+pub fn time_text(round: &Round) -> String {
+    format!("{:.1}s", round.elapsed_ms as f64 / 1000.)
+}
+
+/// The buttons below the rounds, in the order [`Results::handle`] expects their areas in
+const BUTTONS: [&str; 4] = [
+    "Open reference (Enter)",
+    "Export as pack (p)",
+    "New game (n)",
+    "Quit (q)",
+];
+
+/// What should happen after an input was handled
+enum Action {
+    None,
+    NewGame,
+    ExportPack,
+    Quit,
+}
+
+struct Results<'a> {
+    rounds: &'a [Round],
+    selected: usize,
+    offset: usize,
+    code_scroll: u16,
+    /// Feedback on opening the reference
+    status: Option<String>,
+}
+
+impl<'a> Results<'a> {
+    fn select(&mut self, idx: usize) {
+        if idx != self.selected {
+            self.selected = idx;
+            self.code_scroll = 0;
+        }
+    }
+
+    fn open_reference(&mut self) {
+        if let Some(round) = self.rounds.get(self.selected) {
+            let reference = &round.code.reference;
+            self.status = Some(match open(reference) {
+                Ok(()) => format!("Opened {}", reference),
+                Err(e) => format!("Unable to open {}: {}", reference, e),
+            });
+        }
+    }
+
+    fn handle(
+        &mut self,
+        event: Event,
+        rounds_area: Rect,
+        code_area: Rect,
+        buttons: &[Rect],
+    ) -> Action {
+        let last = self.rounds.len().saturating_sub(1);
+        match event {
+            Event::Key(KeyEvent {
+                code: key,
+                modifiers,
+                ..
+            }) => match (key, modifiers) {
+                (Key::Char('c'), KeyModifiers::CONTROL) => return Action::Quit,
+                (Key::Char('q'), _) | (Key::Esc, _) => return Action::Quit,
+                (Key::Char('n'), _) => return Action::NewGame,
+                (Key::Char('p'), _) => return Action::ExportPack,
+                (Key::Up, _) | (Key::Char('k'), _) => self.select(self.selected.saturating_sub(1)),
+                (Key::Down, _) | (Key::Char('j'), _) => self.select((self.selected + 1).min(last)),
+                (Key::PageUp, _) => self.code_scroll = self.code_scroll.saturating_sub(10),
+                (Key::PageDown, _) => self.code_scroll += 10,
+                (Key::Enter, _) | (Key::Char('o'), _) => self.open_reference(),
+                _ => {}
+            },
+            Event::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) => match kind {
+                MouseEventKind::Down(MouseButton::Left) if contains(rounds_area, column, row) => {
+                    let idx = self.offset + (row - rounds_area.y) as usize;
+                    if idx < self.rounds.len() {
+                        self.select(idx);
+                    }
+                }
+                MouseEventKind::Down(MouseButton::Left) => {
+                    match buttons
+                        .iter()
+                        .position(|&button| contains(button, column, row))
+                    {
+                        Some(0) => self.open_reference(),
+                        Some(1) => return Action::ExportPack,
+                        Some(2) => return Action::NewGame,
+                        Some(_) => return Action::Quit,
+                        None => {}
+                    }
+                }
+                MouseEventKind::ScrollUp if contains(code_area, column, row) => {
+                    self.code_scroll = self.code_scroll.saturating_sub(1)
+                }
+                MouseEventKind::ScrollDown if contains(code_area, column, row) => {
+                    self.code_scroll += 1
+                }
+                MouseEventKind::ScrollUp => self.select(self.selected.saturating_sub(1)),
+                MouseEventKind::ScrollDown => self.select((self.selected + 1).min(last)),
+                _ => {}
+            },
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+/// Shows the rounds of the game that just ended, letting the player look at each of them, next to
+/// the high scores. `new_best` highlights that the game was a new personal best.
+///
+/// Returns whether the player wants to play another game.
+pub fn show<B: Backend>(
+    terminal: &mut Terminal<B>,
+    game: &Report,
+    high_scores: &HighScores,
+    new_best: bool,
+) -> Result<bool> {
+    let rounds = &game.rounds;
+    let scores = high_scores.top(&Board::new(&game.settings));
+    let mut results = Results {
+        rounds,
+        selected: 0,
+        offset: 0,
+        code_scroll: 0,
+        status: None,
+    };
+    loop {
+        let mut rounds_area = Rect::default();
+        let mut code_area = Rect::default();
+        let mut buttons = Vec::new();
+        terminal.draw(|f| {
+            let vertical = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(4),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                ])
+                .split(f.size());
+            let text = format!(
+                "Your total points {} in {} rounds!",
+                game.points,
+                rounds.len()
+            );
+            let text = if new_best {
+                Span::styled(
+                    format!("{} New personal best!", text),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::raw(text)
+            };
+            let header = Paragraph::new(vec![Spans::from(text), Spans::from(summary(rounds))])
+                .block(Block::default().title("Results").borders(Borders::ALL));
+            f.render_widget(header, vertical[0]);
+
+            let horizontal = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(vertical[1]);
+            let left = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(TOP as u16 + 3)])
+                .split(horizontal[0]);
+            f.render_widget(high_scores_table(&scores, Some(game.started_at)), left[1]);
+
+            let block = Block::default().title("Rounds").borders(Borders::ALL);
+            rounds_area = block.inner(left[0]);
+            // the header takes up the first row
+            rounds_area.y += 1;
+            rounds_area.height = rounds_area.height.saturating_sub(1);
+            results.offset = scroll_offset(
+                results.offset,
+                Some(results.selected),
+                rounds_area.height as usize,
+                rounds.len(),
+            );
+            let rows = rounds
+                .iter()
+                .enumerate()
+                .skip(results.offset)
+                .take(rounds_area.height as usize)
+                .map(|(idx, round)| {
+                    let row = Row::new(vec![
+                        (idx + 1).to_string(),
+                        score_text(round),
+                        round.language().to_string(),
+                        answer_text(round),
+                        time_text(round),
+                    ]);
+                    if idx == results.selected {
+                        row.style(Style::default().add_modifier(Modifier::REVERSED))
+                    } else {
+                        row
+                    }
+                });
+            let table = Table::new(rows)
+                .header(
+                    Row::new(vec!["#", "Score", "Language", "Your answer", "Time"])
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                )
+                .widths(&[
+                    Constraint::Length(3),
+                    Constraint::Length(5),
+                    Constraint::Percentage(50),
+                    Constraint::Percentage(50),
+                    Constraint::Length(6),
+                ])
+                .block(block);
+            f.render_widget(table, left[0]);
+
+            code_area = horizontal[1];
+            match rounds.get(results.selected) {
+                Some(round) => {
+                    let mut title = format!(
+                        "{} (your answer: {}, {} characters visible",
+                        round.language(),
+                        answer_text(round),
+                        round.visible_chars
+                    );
+                    if round.is_correct() {
+                        title += &format!(", {} = {} points", round.breakdown, round.points);
+                    }
+                    title.push(')');
+                    let paragraph = Paragraph::new(round.code.code.as_str())
+                        .wrap(Wrap { trim: false })
+                        .scroll((results.code_scroll, 0))
+                        .block(Block::default().title(title).borders(Borders::ALL));
+                    f.render_widget(paragraph, horizontal[1]);
+                    let reference = results
+                        .status
+                        .clone()
+                        .unwrap_or_else(|| round.code.reference.clone());
+                    f.render_widget(
+                        Paragraph::new(reference).style(Style::default().fg(Color::Blue)),
+                        vertical[2],
+                    );
+                }
+                None => {
+                    let paragraph = Paragraph::new("No rounds played.")
+                        .block(Block::default().title("Code").borders(Borders::ALL));
+                    f.render_widget(paragraph, horizontal[1]);
+                }
+            }
+
+            f.render_widget(
+                Paragraph::new("↑/↓ select round, PgUp/PgDn scroll code"),
+                vertical[3],
+            );
+            buttons = draw_buttons(f, vertical[3], &BUTTONS, Alignment::Right);
+        })?;
+
+        match results.handle(event::read()?, rounds_area, code_area, &buttons) {
+            Action::None => {}
+            Action::NewGame => return Ok(true),
+            Action::ExportPack => {
+                results.status = Some(match Pack::from_rounds(rounds).save(game.started_at) {
+                    Ok(path) => format!(
+                        "Saved the rounds to {}, play them with --pack",
+                        path.display()
+                    ),
+                    Err(e) => format!("Unable to save the rounds: {}", e),
+                })
+            }
+            Action::Quit => return Ok(false),
+        }
+    }
+}
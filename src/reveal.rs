@@ -0,0 +1,133 @@
+use std::iter;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A tab reaching from one tab stop to the next
+const TAB: &str = "    ";
+
+/// The code of a round prepared for revealing it part by part.
+///
+/// Line endings are normalized to `\n` and tabs expanded to spaces. Positions count graphemes, so
+/// a revealed part never splits a character the way the player sees it.
+pub struct Reveal {
+    text: String,
+    /// Where every grapheme of `text` starts, followed by the end of the text
+    boundaries: Vec<usize>,
+}
+
+impl Reveal {
+    pub fn new(code: &str) -> Self {
+        let mut text = String::with_capacity(code.len());
+        let mut column = 0;
+        for grapheme in code.graphemes(true) {
+            match grapheme {
+                "\r\n" | "\r" | "\n" => {
+                    text.push('\n');
+                    column = 0;
+                }
+                "\t" => {
+                    let spaces = &TAB[column % TAB.len()..];
+                    text.push_str(spaces);
+                    column += spaces.len();
+                }
+                _ => {
+                    text.push_str(grapheme);
+                    column += 1;
+                }
+            }
+        }
+        let boundaries = text
+            .grapheme_indices(true)
+            .map(|(idx, _)| idx)
+            .chain(iter::once(text.len()))
+            .collect();
+        Self { text, boundaries }
+    }
+
+    /// The number of graphemes in the text
+    pub fn len(&self) -> usize {
+        self.boundaries.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The graphemes that are not whitespace, a reveal should start at one of these
+    pub fn origins(&self) -> Vec<usize> {
+        self.boundaries
+            .windows(2)
+            .enumerate()
+            .filter(|(_, bounds)| !self.text[bounds[0]..bounds[1]].trim().is_empty())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// `count` graphemes around `origin`, moved away from the end of the text so as many as
+    /// possible are shown
+    pub fn window(&self, origin: usize, count: usize) -> &str {
+        let count = count.min(self.len());
+        let start = origin.saturating_sub(count / 2).min(self.len() - count);
+        &self.text[self.boundaries[start]..self.boundaries[start + count]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(reveal: &Reveal) -> &str {
+        reveal.window(0, reveal.len())
+    }
+
+    #[test]
+    fn combining_marks_stay_with_their_character() {
+        let reveal = Reveal::new("e\u{301}x");
+        assert_eq!(reveal.len(), 2);
+        assert_eq!(reveal.window(0, 1), "e\u{301}");
+        assert_eq!(reveal.window(1, 1), "x");
+    }
+
+    #[test]
+    fn emoji_sequences_are_a_single_grapheme() {
+        let reveal = Reveal::new("a👩\u{200d}💻b");
+        assert_eq!(reveal.len(), 3);
+        assert_eq!(reveal.window(1, 1), "👩\u{200d}💻");
+        assert_eq!(reveal.origins(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn line_endings_are_normalized() {
+        let reveal = Reveal::new("a\r\nb\rc\n");
+        assert_eq!(text(&reveal), "a\nb\nc\n");
+        assert_eq!(reveal.len(), 6);
+    }
+
+    #[test]
+    fn tabs_reach_the_next_tab_stop() {
+        assert_eq!(text(&Reveal::new("\tx")), "    x");
+        assert_eq!(text(&Reveal::new(" ab\tc")), " ab c");
+        assert_eq!(text(&Reveal::new("abcd\te")), "abcd    e");
+        // the column starts over on every line
+        assert_eq!(text(&Reveal::new("ab\r\n\tc")), "ab\n    c");
+    }
+
+    #[test]
+    fn origins_skip_whitespace() {
+        // the tab after `a` only reaches the next tab stop two columns later
+        assert_eq!(Reveal::new(" a\tb\n").origins(), [1, 4]);
+    }
+
+    #[test]
+    fn window_stays_inside_the_text() {
+        let reveal = Reveal::new("abcdefgh");
+        assert_eq!(reveal.window(0, 4), "abcd");
+        assert_eq!(reveal.window(4, 4), "cdef");
+        assert_eq!(reveal.window(7, 4), "efgh");
+        assert_eq!(reveal.window(3, 100), "abcdefgh");
+
+        let empty = Reveal::new("");
+        assert!(empty.is_empty());
+        assert_eq!(empty.window(0, 4), "");
+    }
+}
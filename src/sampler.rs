@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use rand::prelude::*;
+
+use crate::stats::Stats;
+
+/// Decides which language a round is about and which other languages are offered as options
+pub trait Sampler: Send + Sync {
+    /// Picks `count` of `languages` using `rng`, returning them together with the index of the
+    /// language the code should be in
+    fn sample(
+        &self,
+        rng: &mut dyn RngCore,
+        languages: &[String],
+        count: usize,
+    ) -> (Vec<String>, usize);
+}
+
+/// Every language is equally likely
+pub struct Uniform;
+
+impl Sampler for Uniform {
+    fn sample(
+        &self,
+        rng: &mut dyn RngCore,
+        languages: &[String],
+        count: usize,
+    ) -> (Vec<String>, usize) {
+        let options: Vec<String> = languages.choose_multiple(rng, count).cloned().collect();
+        let idx = rng.gen_range(0..options.len());
+        (options, idx)
+    }
+}
+
+/// Prefers the languages the player often gets wrong and offers the languages they are usually
+/// mistaken for as options, easing off on the ones they always get right
+pub struct Adaptive {
+    /// How likely a language is to be the one to guess, languages never played are missing
+    weights: HashMap<String, f64>,
+    /// For every language, how often it was confused with others in either direction
+    confusions: HashMap<String, HashMap<String, usize>>,
+}
+
+/// The weight of languages that were never played, between a known and an unknown one
+const UNPLAYED_WEIGHT: f64 = 2.;
+
+impl Adaptive {
+    pub fn new(stats: &Stats) -> Self {
+        let weights = stats
+            .languages
+            .iter()
+            .map(|language| {
+                // smoothed so a single round does not decide everything
+                let accuracy = (language.correct as f64 + 1.) / (language.rounds as f64 + 2.);
+                (language.language.clone(), 0.5 + 4. * (1. - accuracy))
+            })
+            .collect();
+        let mut confusions: HashMap<String, HashMap<String, usize>> = HashMap::new();
+        for ((language, answer), &count) in &stats.confusions {
+            *confusions
+                .entry(language.clone())
+                .or_default()
+                .entry(answer.clone())
+                .or_default() += count;
+            *confusions
+                .entry(answer.clone())
+                .or_default()
+                .entry(language.clone())
+                .or_default() += count;
+        }
+        Self {
+            weights,
+            confusions,
+        }
+    }
+}
+
+impl Sampler for Adaptive {
+    fn sample(
+        &self,
+        rng: &mut dyn RngCore,
+        languages: &[String],
+        count: usize,
+    ) -> (Vec<String>, usize) {
+        let language = match languages.choose_weighted(rng, |language| {
+            self.weights
+                .get(language)
+                .copied()
+                .unwrap_or(UNPLAYED_WEIGHT)
+        }) {
+            Ok(language) => language,
+            Err(_) => return Uniform.sample(rng, languages, count),
+        };
+
+        let confused = self.confusions.get(language);
+        let others: Vec<&String> = languages.iter().filter(|&l| l != language).collect();
+        let mut options: Vec<String> = others
+            .choose_multiple_weighted(rng, count.saturating_sub(1), |other| {
+                1. + 3.
+                    * confused
+                        .and_then(|confused| confused.get(*other))
+                        .copied()
+                        .unwrap_or_default() as f64
+            })
+            .map(|others| others.map(|&other| other.clone()).collect())
+            .unwrap_or_default();
+        options.push(language.clone());
+        options.shuffle(rng);
+        let idx = options
+            .iter()
+            .position(|option| option == language)
+            .expect("the language was added to the options");
+        (options, idx)
+    }
+}
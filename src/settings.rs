@@ -0,0 +1,353 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use chrono::{Date, Datelike, Local};
+use clap::ArgEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::party;
+use crate::{CodeProviders, MAX_OPTIONS};
+
+const SETTINGS_FILE: &str = "settings.yml";
+
+/// The rules a game is played with
+#[derive(ArgEnum, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+    /// 5 lives
+    Classic,
+    /// Only a single life
+    #[clap(name = "sudden-death")]
+    SuddenDeath,
+    /// 5 lives, every miss is followed by an explanation how to recognize the language
+    Learning,
+    /// The same rounds for everyone on the same day, see [`Settings::daily`]
+    Daily,
+}
+
+impl Mode {
+    pub const ALL: &'static [Mode] = &[
+        Mode::Classic,
+        Mode::SuddenDeath,
+        Mode::Learning,
+        Mode::Daily,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Classic => "Classic",
+            Mode::SuddenDeath => "Sudden Death",
+            Mode::Learning => "Learning",
+            Mode::Daily => "Daily Challenge",
+        }
+    }
+
+    pub fn lives(self) -> usize {
+        match self {
+            Mode::Classic | Mode::Learning => 5,
+            Mode::SuddenDeath => 1,
+            Mode::Daily => 3,
+        }
+    }
+
+    /// How many rounds a game has at most, the other modes go on until the lives run out
+    pub fn rounds(self) -> Option<usize> {
+        match self {
+            Mode::Daily => Some(10),
+            Mode::Classic | Mode::SuddenDeath | Mode::Learning => None,
+        }
+    }
+}
+
+/// How fast the points of a round decrease and more code is revealed
+#[derive(ArgEnum, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: &'static [Difficulty] =
+        &[Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    pub fn step_duration(self) -> Duration {
+        match self {
+            Difficulty::Easy => Duration::from_secs(3),
+            Difficulty::Normal => Duration::from_secs(2),
+            Difficulty::Hard => Duration::from_secs(1),
+        }
+    }
+}
+
+/// How the points left in a round are counted
+#[derive(ArgEnum, Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum ScoringModel {
+    /// A point is lost every step of the reveal, so all answers within a step are worth the same
+    #[default]
+    Steps,
+    /// The points decrease continuously with the exact reaction time, in hundredths of a point
+    Precise,
+}
+
+impl ScoringModel {
+    pub const ALL: &'static [ScoringModel] = &[ScoringModel::Steps, ScoringModel::Precise];
+    /// How many precise points make up a point of the steps model
+    pub const PRECISION: i32 = 100;
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScoringModel::Steps => "Steps",
+            ScoringModel::Precise => "Precise",
+        }
+    }
+}
+
+/// The rules for the points of a correct answer, the points left in the round (the base) plus
+/// the bonus for a quick answer are multiplied by the streak multiplier
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Scoring {
+    pub model: ScoringModel,
+    /// Every this many correct answers in a row the multiplier increases by one, 0 disables
+    /// streaks
+    pub streak_step: u8,
+    pub max_multiplier: u8,
+    /// Answers given in the first this many reveal steps get the quick bonus
+    pub quick_steps: u8,
+    pub quick_bonus: i32,
+    /// A lost life is regained every this many correct answers in a row, 0 disables it
+    pub regenerate_every: u8,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            model: ScoringModel::Steps,
+            streak_step: 3,
+            max_multiplier: 4,
+            quick_steps: 2,
+            quick_bonus: 3,
+            regenerate_every: 0,
+        }
+    }
+}
+
+/// How the points of a correct answer came together
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct Breakdown {
+    /// The points left in the round
+    pub base: i32,
+    pub bonus: i32,
+    pub multiplier: i32,
+    /// The correct answers in a row, including this one
+    pub streak: usize,
+    /// Whether the answer gave back a lost life, only set by the game as it knows the lives left
+    pub regained_life: bool,
+}
+
+impl Breakdown {
+    pub fn points(&self) -> i32 {
+        (self.base + self.bonus) * self.multiplier
+    }
+}
+
+impl fmt::Display for Breakdown {
+    /// Like `(8 + 3 bonus) ×2`, leaving out what did not apply
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.bonus, self.multiplier) {
+            (0, 1) => write!(f, "{}", self.base),
+            (0, multiplier) => write!(f, "{} ×{}", self.base, multiplier),
+            (bonus, 1) => write!(f, "{} + {} bonus", self.base, bonus),
+            (bonus, multiplier) => write!(f, "({} + {} bonus) ×{}", self.base, bonus, multiplier),
+        }
+    }
+}
+
+impl Scoring {
+    /// The base points of an answer with `points` of `max_points` left and `time_left` being the
+    /// share of the round's time that was not used yet
+    pub fn base(&self, points: i32, max_points: i32, time_left: f64) -> i32 {
+        match self.model {
+            ScoringModel::Steps => points,
+            ScoringModel::Precise => (time_left.clamp(0., 1.)
+                * (max_points * ScoringModel::PRECISION) as f64)
+                .round() as i32,
+        }
+    }
+
+    /// Scores a correct answer with `points` of `max_points` left and the share `time_left` of
+    /// the round's time, `streak` being the correct answers in a row including this one
+    pub fn score(&self, points: i32, max_points: i32, time_left: f64, streak: usize) -> Breakdown {
+        let multiplier = match self.streak_step {
+            0 => 1,
+            step => (1 + (streak - 1) / step as usize).min(self.max_multiplier.max(1) as usize),
+        };
+        let scale = match self.model {
+            ScoringModel::Steps => 1,
+            ScoringModel::Precise => ScoringModel::PRECISION,
+        };
+        Breakdown {
+            base: self.base(points, max_points, time_left),
+            bonus: if max_points - points < self.quick_steps as i32 {
+                self.quick_bonus * scale
+            } else {
+                0
+            },
+            multiplier: multiplier as i32,
+            streak,
+            regained_life: false,
+        }
+    }
+
+    /// Whether a correct answer with `streak` correct answers in a row (including it) gives back a
+    /// lost life
+    pub fn regenerates(&self, streak: usize) -> bool {
+        self.regenerate_every != 0 && streak.is_multiple_of(self.regenerate_every as usize)
+    }
+}
+
+/// The settings chosen in the menu, persisted as the defaults for the next game
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub mode: Mode,
+    pub difficulty: Difficulty,
+    pub provider: CodeProviders,
+    /// The languages to guess from, all languages on GitHub if empty
+    pub languages: Vec<String>,
+    pub options: u8,
+    /// How many players share the screen, see [`crate::party::Party`]
+    pub players: u8,
+    /// Whether the languages are chosen based on the past rounds, see [`crate::sampler::Adaptive`]
+    pub adaptive: bool,
+    pub scoring: Scoring,
+    /// Named selections of languages saved in the language picker
+    pub language_sets: BTreeMap<String, Vec<String>>,
+}
+
+fn default_language_sets() -> BTreeMap<String, Vec<String>> {
+    let sets: &[(&str, &[&str])] = &[
+        (
+            "web",
+            &[
+                "CSS",
+                "HTML",
+                "JavaScript",
+                "PHP",
+                "SCSS",
+                "TypeScript",
+                "Vue",
+            ],
+        ),
+        ("jvm", &["Clojure", "Groovy", "Java", "Kotlin", "Scala"]),
+        (
+            "systems",
+            &["Assembly", "C", "C++", "D", "Go", "Rust", "Zig"],
+        ),
+    ];
+    sets.iter()
+        .map(|(name, languages)| {
+            (
+                name.to_string(),
+                languages.iter().map(|l| l.to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Classic,
+            difficulty: Difficulty::Normal,
+            provider: CodeProviders::GitHub,
+            languages: Vec::new(),
+            options: 4,
+            players: 1,
+            adaptive: false,
+            scoring: Scoring::default(),
+            language_sets: default_language_sets(),
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings saved by the last game, falling back to the defaults if there are none
+    pub fn load() -> Result<Self> {
+        let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+        Ok(match dirs.find_config_file(SETTINGS_FILE) {
+            Some(path) => serde_yaml::from_slice(&fs::read(path)?)?,
+            None => Settings::default(),
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+        fs::write(
+            dirs.place_config_file(SETTINGS_FILE)?,
+            serde_yaml::to_string(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// The settings of the daily challenge, the same for everyone so the results can be compared.
+    ///
+    /// Only the language sets are kept.
+    pub fn daily(&self) -> Self {
+        Self {
+            mode: Mode::Daily,
+            provider: CodeProviders::Pack,
+            language_sets: self.language_sets.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// The seed of the daily challenge on `date`
+    pub fn daily_seed(date: Date<Local>) -> u64 {
+        date.num_days_from_ce() as u64
+    }
+
+    /// Checks that a game can be started with these settings
+    pub fn validate(&self) -> Result<()> {
+        if self.options < 2 || self.options as usize > MAX_OPTIONS {
+            bail!(
+                "Invalid number of options {}! Needs to be between 2 and {}",
+                self.options,
+                MAX_OPTIONS
+            );
+        }
+        if !self.languages.is_empty() && self.languages.len() < self.options as usize {
+            bail!("Not enough languages! Need at least {}", self.options);
+        }
+        if self.players < 1 || self.players as usize > party::MAX_PLAYERS {
+            bail!(
+                "Invalid number of players {}! Needs to be between 1 and {}",
+                self.players,
+                party::MAX_PLAYERS
+            );
+        }
+        if self.players > 1 {
+            if self.options as usize > party::MAX_PARTY_OPTIONS {
+                bail!(
+                    "Too many options for more than one player! Can be at most {}",
+                    party::MAX_PARTY_OPTIONS
+                );
+            }
+            if matches!(self.mode, Mode::Learning | Mode::Daily) {
+                bail!("{} can only be played alone!", self.mode.name());
+            }
+        }
+        Ok(())
+    }
+}
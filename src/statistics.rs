@@ -0,0 +1,205 @@
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode as Key, KeyEvent, MouseEvent, MouseEventKind};
+use language_guesser::stats::{LanguageStats, Stats};
+use tui::backend::Backend;
+use tui::layout::{Constraint, Direction, Layout};
+use tui::style::{Color, Modifier, Style};
+use tui::widgets::{BarChart, Block, Borders, Cell, Paragraph, Row, Table};
+use tui::Terminal;
+
+use crate::ui::scroll_offset;
+
+fn average(stats: &LanguageStats) -> String {
+    stats
+        .average_ms()
+        .map(|ms| format!("{:.1}s", ms as f64 / 1000.))
+        .unwrap_or_else(|| String::from("---"))
+}
+
+/// Prints the statistics as tables, used by the `stats` subcommand
+pub fn print(stats: &Stats) {
+    use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+    use comfy_table::presets::UTF8_FULL;
+    use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
+
+    if stats.languages.is_empty() {
+        println!("No rounds played yet.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            ["Language", "Rounds", "Accuracy", "Average time"]
+                .iter()
+                .map(|s| Cell::new(s).set_alignment(CellAlignment::Center)),
+        );
+    for language in &stats.languages {
+        table.add_row(vec![
+            Cell::new(&language.language),
+            Cell::new(language.rounds),
+            Cell::new(format!("{:.0}%", language.accuracy())),
+            Cell::new(average(language)),
+        ]);
+    }
+    println!("{}", table);
+
+    let confusions = stats.top_confusions();
+    if !confusions.is_empty() {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(
+                ["Language", "Mistaken for", "Times"]
+                    .iter()
+                    .map(|s| Cell::new(s).set_alignment(CellAlignment::Center)),
+            );
+        for (language, answer, count) in confusions.into_iter().take(20) {
+            table.add_row(vec![
+                Cell::new(language),
+                Cell::new(answer),
+                Cell::new(count),
+            ]);
+        }
+        println!("\nMost common confusions:\n{}", table);
+    }
+}
+
+/// Cuts `name` to `width` characters to fit into a narrow column
+fn short(name: &str, width: usize) -> String {
+    name.chars().take(width).collect()
+}
+
+/// Shows the statistics until the player goes back
+pub fn show<B: Backend>(terminal: &mut Terminal<B>, stats: &Stats) -> Result<()> {
+    let mut offset = 0;
+    let matrix = stats.matrix_languages();
+    loop {
+        terminal.draw(|f| {
+            let vertical = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(50),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                ])
+                .split(f.size());
+            let horizontal = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(vertical[0]);
+
+            // borders and header
+            let visible = horizontal[0].height.saturating_sub(3) as usize;
+            offset = scroll_offset(offset, None, visible, stats.languages.len());
+            let rows = stats.languages.iter().skip(offset).map(|language| {
+                Row::new(vec![
+                    language.language.clone(),
+                    language.rounds.to_string(),
+                    format!("{:.0}%", language.accuracy()),
+                    average(language),
+                ])
+            });
+            let title = if stats.languages.is_empty() {
+                "Languages (no rounds played yet)"
+            } else {
+                "Languages"
+            };
+            let table = Table::new(rows)
+                .header(
+                    Row::new(vec!["Language", "Rounds", "Accuracy", "Avg time"])
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                )
+                .widths(&[
+                    Constraint::Percentage(100),
+                    Constraint::Length(6),
+                    Constraint::Length(8),
+                    Constraint::Length(8),
+                ])
+                .block(Block::default().title(title).borders(Borders::ALL));
+            f.render_widget(table, horizontal[0]);
+
+            // the worst languages, as many as fit
+            let bars = (horizontal[1].width.saturating_sub(2) / 6) as usize;
+            let labels: Vec<_> = stats
+                .languages
+                .iter()
+                .take(bars)
+                .map(|language| short(&language.language, 5))
+                .collect();
+            let data: Vec<_> = labels
+                .iter()
+                .zip(&stats.languages)
+                .map(|(label, language)| (label.as_str(), language.accuracy().round() as u64))
+                .collect();
+            let chart = BarChart::default()
+                .block(
+                    Block::default()
+                        .title("Accuracy in %")
+                        .borders(Borders::ALL),
+                )
+                .data(&data)
+                .max(100)
+                .bar_width(5)
+                .bar_gap(1)
+                .bar_style(Style::default().fg(Color::Yellow))
+                .value_style(Style::default().fg(Color::Black).bg(Color::Yellow));
+            f.render_widget(chart, horizontal[1]);
+
+            let mut header = vec![Cell::from("Language \\ Answer")];
+            header.extend(
+                matrix
+                    .iter()
+                    .map(|answer| Cell::from(short(&answer.language, 7))),
+            );
+            let rows = matrix.iter().map(|language| {
+                let mut cells = vec![Cell::from(language.language.as_str())];
+                cells.extend(matrix.iter().map(|answer| {
+                    let count = stats.count(language, answer);
+                    let style = match count {
+                        0 => Style::default().fg(Color::DarkGray),
+                        _ if language.language == answer.language => {
+                            Style::default().fg(Color::Green)
+                        }
+                        _ => Style::default().fg(Color::Red),
+                    };
+                    Cell::from(count.to_string()).style(style)
+                }));
+                Row::new(cells)
+            });
+            let mut widths = vec![Constraint::Length(18)];
+            widths.extend(matrix.iter().map(|_| Constraint::Length(7)));
+            let table = Table::new(rows)
+                .header(Row::new(header).style(Style::default().add_modifier(Modifier::BOLD)))
+                .widths(&widths)
+                .block(
+                    Block::default()
+                        .title("Confusion matrix of the most played languages")
+                        .borders(Borders::ALL),
+                );
+            f.render_widget(table, vertical[1]);
+
+            f.render_widget(Paragraph::new("↑/↓ scroll, Esc back"), vertical[2]);
+        })?;
+
+        match event::read()? {
+            Event::Key(KeyEvent { code: key, .. }) => match key {
+                Key::Up | Key::Char('k') => offset = offset.saturating_sub(1),
+                Key::Down | Key::Char('j') => offset += 1,
+                Key::Esc | Key::Char('q') => return Ok(()),
+                _ => {}
+            },
+            Event::Mouse(MouseEvent { kind, .. }) => match kind {
+                MouseEventKind::ScrollUp => offset = offset.saturating_sub(1),
+                MouseEventKind::ScrollDown => offset += 1,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+
+use crate::engine::Outcome;
+use crate::history::Entry;
+
+/// How many of the most played languages are shown in the confusion matrix
+const MATRIX_SIZE: usize = 8;
+
+/// How well a single language is recognized
+pub struct LanguageStats {
+    pub language: String,
+    pub rounds: usize,
+    pub correct: usize,
+    /// Rounds in which an answer was given, unlike ones that timed out
+    answered: usize,
+    elapsed_ms: u64,
+}
+
+impl LanguageStats {
+    /// The share of correctly guessed rounds in percent
+    pub fn accuracy(&self) -> f64 {
+        self.correct as f64 * 100. / self.rounds as f64
+    }
+
+    /// The average time to answer, if the language was ever answered in time
+    pub fn average_ms(&self) -> Option<u64> {
+        if self.answered == 0 {
+            None
+        } else {
+            Some(self.elapsed_ms / self.answered as u64)
+        }
+    }
+}
+
+/// Statistics over all rounds played so far
+pub struct Stats {
+    /// Worst recognized language first
+    pub languages: Vec<LanguageStats>,
+    /// How often the first language was mistaken for the second one
+    pub confusions: BTreeMap<(String, String), usize>,
+}
+
+impl Stats {
+    /// Aggregates the `history`, rounds the player quit, skipped or lost to another player are left
+    /// out
+    pub fn new(history: &[Entry]) -> Self {
+        let mut languages = BTreeMap::<&str, LanguageStats>::new();
+        let mut confusions = BTreeMap::new();
+        for entry in history
+            .iter()
+            .filter(|e| !matches!(e.outcome, Outcome::Quit | Outcome::Skip | Outcome::Beaten))
+        {
+            let stats = languages
+                .entry(&entry.language)
+                .or_insert_with(|| LanguageStats {
+                    language: entry.language.clone(),
+                    rounds: 0,
+                    correct: 0,
+                    answered: 0,
+                    elapsed_ms: 0,
+                });
+            stats.rounds += 1;
+            if let Some(answer) = &entry.answer {
+                stats.answered += 1;
+                stats.elapsed_ms += entry.elapsed_ms;
+                if entry.is_correct() {
+                    stats.correct += 1;
+                } else {
+                    *confusions
+                        .entry((entry.language.clone(), answer.clone()))
+                        .or_default() += 1;
+                }
+            }
+        }
+        let mut languages: Vec<_> = languages.into_values().collect();
+        languages.sort_by(|a, b| {
+            a.accuracy()
+                .partial_cmp(&b.accuracy())
+                .unwrap_or(Ordering::Equal)
+                .then(b.rounds.cmp(&a.rounds))
+        });
+        Self {
+            languages,
+            confusions,
+        }
+    }
+
+    /// The confusions, most common first
+    pub fn top_confusions(&self) -> Vec<(&str, &str, usize)> {
+        let mut confusions: Vec<_> = self
+            .confusions
+            .iter()
+            .map(|((language, answer), &count)| (language.as_str(), answer.as_str(), count))
+            .collect();
+        confusions.sort_by_key(|&(_, _, count)| Reverse(count));
+        confusions
+    }
+
+    /// The most played languages, in the order they appear in the confusion matrix
+    pub fn matrix_languages(&self) -> Vec<&LanguageStats> {
+        let mut languages: Vec<_> = self.languages.iter().collect();
+        languages.sort_by_key(|language| Reverse(language.rounds));
+        languages.truncate(MATRIX_SIZE);
+        languages
+    }
+
+    /// How often `language` was answered with `answer`
+    pub fn count(&self, language: &LanguageStats, answer: &LanguageStats) -> usize {
+        if language.language == answer.language {
+            language.correct
+        } else {
+            self.confusions
+                .get(&(language.language.clone(), answer.language.clone()))
+                .copied()
+                .unwrap_or_default()
+        }
+    }
+}
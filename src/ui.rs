@@ -0,0 +1,449 @@
+use std::collections::BTreeSet;
+use std::io::{self, Stdout};
+use std::ops::{Deref, DerefMut};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use crossterm::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use language_guesser::engine::{Current, Game};
+use language_guesser::lifelines::{Lifeline, Lifelines};
+use tui::backend::{Backend, CrosstermBackend};
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Row, Table, Wrap};
+use tui::{Frame, Terminal};
+
+use crate::highscores::{HighScore, TOP};
+
+/// The terminal set up by [`setup_terminal`], it is restored when dropped so that neither an
+/// error nor a panic leaves the shell in raw mode
+pub struct Tui(Terminal<CrosstermBackend<Stdout>>);
+
+impl Deref for Tui {
+    type Target = Terminal<CrosstermBackend<Stdout>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Tui {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        // there is nobody left to report a failure to
+        execute!(
+            self.0.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableFocusChange
+        )
+        .ok();
+        disable_raw_mode().ok();
+    }
+}
+
+/// Switches the terminal to the alternate screen in raw mode with mouse capture and focus events
+/// until the returned [`Tui`] is dropped
+pub fn setup_terminal() -> Result<Tui> {
+    let mut tui = Tui(Terminal::new(CrosstermBackend::new(io::stdout()))?);
+    enable_raw_mode()?;
+    execute!(
+        tui.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
+    tui.clear()?;
+    Ok(tui)
+}
+
+/// The keys used to pick an option directly, in the order of the options.
+///
+/// `h`, `j`, `k` and `l` are left out as they are used for navigating the list. There is one for
+/// each of the [`language_guesser::MAX_OPTIONS`].
+pub const HOTKEYS: &[char] = &[
+    '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'i', 'm', 'n',
+    'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+/// Selection and scroll position of the list of languages to guess from.
+///
+/// The options a lifeline took away are passed to the moving methods, which skip them.
+#[derive(Default)]
+pub struct OptionsState {
+    selected: Option<usize>,
+    offset: usize,
+}
+
+impl OptionsState {
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn select_next(&mut self, len: usize, removed: &BTreeSet<usize>) {
+        for _ in 0..len {
+            self.selected = Some(match self.selected {
+                Some(selected) if selected + 1 < len => selected + 1,
+                _ => 0,
+            });
+            if self
+                .selected
+                .filter(|selected| removed.contains(selected))
+                .is_none()
+            {
+                break;
+            }
+        }
+    }
+
+    pub fn select_previous(&mut self, len: usize, removed: &BTreeSet<usize>) {
+        for _ in 0..len {
+            self.selected = Some(match self.selected {
+                Some(selected) if selected > 0 => selected - 1,
+                _ => len.saturating_sub(1),
+            });
+            if self
+                .selected
+                .filter(|selected| removed.contains(selected))
+                .is_none()
+            {
+                break;
+            }
+        }
+    }
+
+    /// The option shown at the given terminal position, if the options table was rendered to
+    /// `area`.
+    pub fn option_at(&self, area: Rect, len: usize, column: u16, row: u16) -> Option<usize> {
+        let inner = Block::default().borders(Borders::ALL).inner(area);
+        if contains(inner, column, row) {
+            Some(self.offset + (row - inner.y) as usize).filter(|&idx| idx < len)
+        } else {
+            None
+        }
+    }
+
+    /// Builds the table for the options, only containing the rows fitting in `height` (including
+    /// the borders) and scrolling as needed to keep the selection visible.
+    pub fn table<'a>(
+        &mut self,
+        options: &'a [String],
+        removed: &BTreeSet<usize>,
+        height: u16,
+    ) -> Table<'a> {
+        if self
+            .selected
+            .filter(|selected| removed.contains(selected))
+            .is_some()
+        {
+            self.selected = None;
+        }
+        let visible = (height as usize).saturating_sub(2).max(1);
+        self.offset = scroll_offset(self.offset, self.selected, visible, options.len());
+
+        let title = if options.len() > visible {
+            format!(
+                "Languages {}-{}/{}",
+                self.offset + 1,
+                (self.offset + visible).min(options.len()),
+                options.len()
+            )
+        } else {
+            String::from("Languages")
+        };
+
+        let selected = self.selected;
+        let removed = removed.clone();
+        Table::new(
+            options
+                .iter()
+                .zip(HOTKEYS)
+                .enumerate()
+                .skip(self.offset)
+                .take(visible)
+                .map(move |(idx, (language, hotkey))| {
+                    let row = Row::new(vec![hotkey.to_string(), language.to_string()]);
+                    if removed.contains(&idx) {
+                        row.style(
+                            Style::default()
+                                .fg(Color::DarkGray)
+                                .add_modifier(Modifier::CROSSED_OUT),
+                        )
+                    } else if Some(idx) == selected {
+                        row.style(Style::default().add_modifier(Modifier::REVERSED))
+                    } else {
+                        row
+                    }
+                }),
+        )
+        .widths(&[Constraint::Length(3), Constraint::Percentage(100)])
+        .block(Block::default().title(title).borders(Borders::ALL))
+    }
+}
+
+/// The indicator for the header, used up lifelines are crossed out
+pub fn lifelines_spans(lifelines: &Lifelines) -> Spans<'static> {
+    let mut spans = vec![Span::raw("Lifelines:")];
+    for (idx, &lifeline) in Lifeline::ALL.iter().enumerate() {
+        let style = if lifelines.remaining(lifeline) > 0 {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::CROSSED_OUT)
+        };
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!("F{} {}", idx + 1, lifeline.name()),
+            style,
+        ));
+    }
+    Spans::from(spans)
+}
+
+/// Draws the round of `game` that is being played with `help` above it.
+///
+/// Returns where the options, the code and the button continuing a paused round were drawn to map
+/// mouse events to them.
+pub fn draw_game<B: Backend>(
+    f: &mut Frame<B>,
+    game: &Game,
+    help: &str,
+    options_state: &mut OptionsState,
+    code_scroll: &mut u16,
+) -> (Rect, Rect, Rect) {
+    let current = game.current().expect("a round is being played");
+    let options = &current.code().options;
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(6), Constraint::Percentage(80)].as_ref())
+        .split(f.size());
+    let block = Block::default().borders(Borders::ALL);
+    {
+        let inner = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(2),
+                    Constraint::Length(1),
+                    Constraint::Percentage(100),
+                ]
+                .as_ref(),
+            )
+            .split(block.inner(vertical[0]));
+        f.render_widget(Paragraph::new(help), inner[0]);
+        f.render_widget(Paragraph::new(lifelines_spans(game.lifelines())), inner[1]);
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                ]
+                .as_ref(),
+            )
+            .split(inner[2]);
+        let p = Paragraph::new(format!(
+            "Total Points: {} (streak {}, next ×{})",
+            game.points(),
+            game.streak(),
+            game.next_multiplier()
+        ));
+        f.render_widget(p, bottom[0]);
+
+        let p = Paragraph::new(format!("Round Points: {}", game.round_points()));
+        f.render_widget(p, bottom[1]);
+
+        let p = Paragraph::new(format!("Lives: {}", "🫀".repeat(game.lives())));
+        f.render_widget(p, bottom[2]);
+    }
+    f.render_widget(block, vertical[0]);
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(options_width(options).max(20).min(vertical[1].width / 3)),
+            Constraint::Min(0),
+        ])
+        .split(vertical[1]);
+    let table = options_state.table(options, current.removed(), horizontal[0].height);
+    f.render_widget(table, horizontal[0]);
+    let resume = draw_code(f, current, horizontal[1], code_scroll, "Space or Esc");
+    (horizontal[0], horizontal[1], resume)
+}
+
+/// Draws the code revealed in the `current` round to `area`, or a popup if it is paused telling
+/// that `continue_keys` continue the round.
+///
+/// Returns where the button continuing the round was drawn, an empty area if it is not paused or
+/// no `continue_keys` are given as the player can't continue it.
+pub fn draw_code<B: Backend>(
+    f: &mut Frame<B>,
+    current: &Current,
+    area: Rect,
+    code_scroll: &mut u16,
+    continue_keys: &str,
+) -> Rect {
+    let shown_text = current.shown_text();
+    *code_scroll = (*code_scroll).min(shown_text.lines().count() as u16);
+    let code = Paragraph::new(shown_text)
+        .wrap(Wrap { trim: false })
+        .scroll((*code_scroll, 0))
+        .block(Block::default().title("Code").borders(Borders::ALL));
+
+    if current.is_paused() {
+        let code = Block::default().title("Code").borders(Borders::ALL);
+        f.render_widget(code, area);
+        let area = centered(f.size(), 40, 5);
+        let popup = Paragraph::new(format!("\nPress {} to continue.", continue_keys))
+            .alignment(Alignment::Center)
+            .block(Block::default().title("Paused").borders(Borders::ALL));
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+        if !continue_keys.is_empty() {
+            let inner = Block::default().borders(Borders::ALL).inner(area);
+            let last = Rect {
+                y: inner.bottom().saturating_sub(1),
+                height: inner.height.min(1),
+                ..inner
+            };
+            return draw_buttons(f, last, &["Continue"], Alignment::Center)[0];
+        }
+    } else {
+        f.render_widget(code, area);
+    }
+    Rect::default()
+}
+
+/// Draws `labels` as buttons next to each other in the first row of `area`, placed by `alignment`.
+///
+/// Returns where each of them was drawn to map clicks to them.
+pub fn draw_buttons<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    labels: &[&str],
+    alignment: Alignment,
+) -> Vec<Rect> {
+    // a space on both sides of the label and between the buttons
+    let widths: Vec<_> = labels
+        .iter()
+        .map(|label| label.chars().count() as u16 + 2)
+        .collect();
+    let total = widths.iter().sum::<u16>() + widths.len().saturating_sub(1) as u16;
+    let mut x = area.x
+        + match alignment {
+            Alignment::Left => 0,
+            Alignment::Center => area.width.saturating_sub(total) / 2,
+            Alignment::Right => area.width.saturating_sub(total),
+        };
+    labels
+        .iter()
+        .zip(widths)
+        .map(|(label, width)| {
+            let button = Rect::new(x, area.y, width.min(area.right() - x), area.height.min(1));
+            f.render_widget(
+                Paragraph::new(format!(" {} ", label))
+                    .style(Style::default().add_modifier(Modifier::REVERSED)),
+                button,
+            );
+            x = (x + width + 1).min(area.right());
+            button
+        })
+        .collect()
+}
+
+/// The table of the best `scores` (including the borders [`TOP`] + 3 rows high), the one with
+/// the date `current` is highlighted as the game just played
+pub fn high_scores_table<'a>(scores: &[&HighScore], current: Option<DateTime<Local>>) -> Table<'a> {
+    let rows: Vec<_> = scores
+        .iter()
+        .take(TOP)
+        .enumerate()
+        .map(|(idx, score)| {
+            let row = Row::new(vec![
+                (idx + 1).to_string(),
+                score.points.to_string(),
+                score.rounds.to_string(),
+                score.date.format("%Y-%m-%d %H:%M").to_string(),
+            ]);
+            if Some(score.date) == current {
+                row.style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                row
+            }
+        })
+        .collect();
+    let title = if rows.is_empty() {
+        "High scores (none yet)"
+    } else {
+        "High scores"
+    };
+    Table::new(rows)
+        .header(
+            Row::new(vec!["#", "Points", "Rounds", "Date"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .widths(&[
+            Constraint::Length(2),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(16),
+        ])
+        .block(Block::default().title(title).borders(Borders::ALL))
+}
+
+/// The first row to show in a list of `len` rows with space for `visible` rows, moving `offset`
+/// as little as possible to keep `selected` visible
+pub fn scroll_offset(offset: usize, selected: Option<usize>, visible: usize, len: usize) -> usize {
+    let offset = match selected {
+        Some(selected) if selected < offset => selected,
+        Some(selected) if selected >= offset + visible => selected + 1 - visible,
+        _ => offset,
+    };
+    offset.min(len.saturating_sub(visible))
+}
+
+/// The width needed to display the options without cutting of any language names
+pub fn options_width(options: &[String]) -> u16 {
+    // borders, hotkey column and column spacing
+    options
+        .iter()
+        .map(|language| language.chars().count())
+        .max()
+        .unwrap_or_default() as u16
+        + 6
+}
+
+/// Whether the terminal position is inside `area`, used to map mouse events to widgets
+pub fn contains(area: Rect, column: u16, row: u16) -> bool {
+    area.left() <= column && column < area.right() && area.top() <= row && row < area.bottom()
+}
+
+/// A rectangle of at most `width` by `height` centered in `area`, used for popups
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}